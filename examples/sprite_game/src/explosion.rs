@@ -4,15 +4,13 @@ use playdate::{
 };
 
 pub struct Explosion {
-    frame: usize,
     x: f32,
     y: f32,
 }
 
 impl Explosion {
     pub fn new(x: f32, y: f32) -> Self {
-        let frame = 0;
-        Self { x, y, frame }
+        Self { x, y }
     }
 }
 
@@ -21,7 +19,7 @@ impl GameObject<State> for Explosion {
         let state = pd.data();
 
         builder
-            .image(state.explosion_images[0].clone(), BitmapFlip::Unflipped)
+            .animation(state.explosion_animation.clone(), BitmapFlip::Unflipped)
             .move_to(self.x, self.y)
             .z_index(2_000)
             .tag(1)
@@ -30,16 +28,10 @@ impl GameObject<State> for Explosion {
     }
 
     fn update(&mut self, ctx: UpdateContext<State>) -> Persistance {
-        let state = ctx.pd.data();
-        self.frame += 1;
-
-        if self.frame > 7 {
+        if !ctx.sprite.is_animating_image() {
             return Persistance::Destroy;
         }
 
-        let frame_image = state.explosion_images[self.frame].clone();
-        ctx.sprite.set_image(frame_image, BitmapFlip::Unflipped);
-
         Persistance::Keep
     }
 }
@@ -12,37 +12,79 @@ mod explosion;
 mod player;
 mod state;
 
-use alloc::boxed::Box;
+use alloc::{boxed::Box, format};
 use background::Background;
 use background_plane::BackgroundPlane;
 use bullet::Bullet;
 use core::cmp;
 use enemy_plane::EnemyPlane;
-use playdate::{rng, ButtonState, Buttons, FrameResult, Playdate};
-use playdate_init::pd_app;
+use playdate::{cstr, rng, ButtonState, Buttons, FrameResult, OptionsMenuItem, Playdate};
+use playdate_init::{pd_app, MenuOptions};
 use player::Player;
 use state::State;
 
 #[pd_app(init = "new", update = "update", state = "State")]
-struct Game;
+struct Game {
+    difficulty: OptionsMenuItem<Difficulty>,
+    current_difficulty: Difficulty,
+}
+
+#[derive(Clone, Copy, Eq, MenuOptions, PartialEq)]
+enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    #[option("Chaos")]
+    Insane,
+}
+
+impl Difficulty {
+    fn max_enemies(self) -> i32 {
+        match self {
+            Self::Easy => 5,
+            Self::Normal => 10,
+            Self::Hard => 20,
+            Self::Insane => 60,
+        }
+    }
+}
 
 impl Game {
     fn new(pd: &mut Playdate<State>) -> Self {
         pd.display_mut().set_refresh_rate(20.0);
 
-        let now = pd.system().seconds_since_epoch().seconds;
+        let now = pd.system().seconds_since_epoch().as_secs() as u32;
         rng::set_seed(now);
 
+        pd.console_mut().register_builtins();
+
+        pd.set_menu_image_renderer(0, |pd, visible| {
+            let score = format!("score: {}", pd.data().score);
+            let enemies = format!("enemies: {}", pd.data().enemy_count);
+            let gfx = pd.graphics_mut();
+            gfx.draw_str(&score, visible.x + 20, 100);
+            gfx.draw_str(&enemies, visible.x + 20, 120);
+        });
+
         let player = Box::new(Player);
         let background = Box::new(Background::default());
         pd.sprite_mut().new_sprite(player);
         pd.sprite_mut().new_sprite(background);
 
-        Self {}
+        let mut difficulty = pd
+            .system_mut()
+            .add_typed_options_menu_item(cstr!("difficulty"), || {});
+        difficulty.set_value(Difficulty::Normal);
+
+        Self {
+            difficulty,
+            current_difficulty: Difficulty::Normal,
+        }
     }
 
     fn update(&mut self, pd: &mut Playdate<State>) -> FrameResult {
         self.check_buttons(pd);
+        self.check_difficulty(pd);
         self.check_crank(pd);
         self.spawn_enemy_if_needed(pd);
         self.spawn_background_plane_if_needed(pd);
@@ -51,6 +93,15 @@ impl Game {
         FrameResult::Update
     }
 
+    fn check_difficulty(&mut self, pd: &mut Playdate<State>) {
+        let difficulty = self.difficulty.value();
+
+        if difficulty != self.current_difficulty {
+            self.current_difficulty = difficulty;
+            pd.data_mut().max_enemies = difficulty.max_enemies();
+        }
+    }
+
     fn check_crank(&mut self, pd: &mut Playdate<State>) {
         let change = pd.system().crank_change();
 
@@ -1,5 +1,5 @@
-use alloc::{rc::Rc, vec::Vec};
-use playdate::{cstr, format_string, Bitmap, Playdate, PlaydateState, Rect};
+use alloc::rc::Rc;
+use playdate::{cstr, Animation, Bitmap, BitmapTable, Playdate, PlaydateState, Rect};
 
 pub struct State {
     pub score: u32,
@@ -13,7 +13,7 @@ pub struct State {
     pub enemy_plane_height: f32,
     pub bg_plane_height: f32,
 
-    pub explosion_images: Vec<Rc<Bitmap>>,
+    pub explosion_animation: Animation,
     pub bullet_image: Rc<Bitmap>,
     pub enemy_plane_image: Rc<Bitmap>,
     pub background_plane_image: Rc<Bitmap>,
@@ -22,12 +22,8 @@ pub struct State {
 impl PlaydateState for State {
     fn init(pd: &mut Playdate<()>) -> Self {
         let gfx = pd.graphics();
-        let mut explosion_images = Vec::with_capacity(8);
-        for i in 0..8 {
-            let path = format_string!(cstr!("images/explosion/%d"), i + 1);
-            let image = gfx.load_bitmap(&path).unwrap();
-            explosion_images.push(Rc::new(image));
-        }
+        let explosion_table = BitmapTable::load(cstr!("images/explosion")).unwrap();
+        let explosion_animation = Animation::from_table(&explosion_table, 50);
 
         let enemy_plane_image = Rc::new(gfx.load_bitmap(cstr!("images/plane1")).unwrap());
         let background_plane_image = Rc::new(gfx.load_bitmap(cstr!("images/plane2")).unwrap());
@@ -57,7 +53,7 @@ impl PlaydateState for State {
 
             bullet_image,
             enemy_plane_image,
-            explosion_images,
+            explosion_animation,
             background_plane_image,
         }
     }
@@ -0,0 +1,251 @@
+use crate::{
+    bitmap::{Bitmap, BitmapTable},
+    system::System,
+};
+use alloc::{rc::Rc, vec, vec::Vec};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AnimationMode {
+    // Stops on the last frame
+    Once,
+    Loop,
+    // Plays forwards and then backwards, without repeating the first and last frames
+    PingPong,
+}
+
+#[derive(Clone, Debug)]
+pub struct Animation {
+    frames: Vec<Rc<Bitmap>>,
+    durations: Vec<u32>,
+    mode: AnimationMode,
+    elapsed: u32,
+    last_tick: Option<u32>,
+}
+
+impl Animation {
+    // Shows each frame for `frame_duration` milliseconds
+    pub fn new(frames: Vec<Rc<Bitmap>>, frame_duration: u32) -> Self {
+        let durations = vec![frame_duration; frames.len()];
+
+        Self {
+            frames,
+            durations,
+            mode: AnimationMode::Once,
+            elapsed: 0,
+            last_tick: None,
+        }
+    }
+
+    // Sprites keep their images alive, so the frames are copied out of the table
+    pub fn from_table(table: &BitmapTable, frame_duration: u32) -> Self {
+        let frames = table
+            .iter()
+            .map(|frame| Rc::new(frame.duplicate()))
+            .collect();
+        Self::new(frames, frame_duration)
+    }
+
+    pub fn mode(mut self, mode: AnimationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    // Overrides the duration of the first `durations.len()` frames
+    pub fn durations(mut self, durations: &[u32]) -> Self {
+        for (duration, &value) in self.durations.iter_mut().zip(durations) {
+            *duration = value;
+        }
+
+        self
+    }
+
+    pub fn frame_duration(mut self, index: usize, duration: u32) -> Self {
+        if let Some(value) = self.durations.get_mut(index) {
+            *value = duration;
+        }
+
+        self
+    }
+
+    pub fn advance(&mut self, delta: u32) -> Option<Rc<Bitmap>> {
+        self.elapsed = self.elapsed.saturating_add(delta);
+        self.frame()
+    }
+
+    pub fn update(&mut self, system: &System) -> Option<Rc<Bitmap>> {
+        // The first update only records the clock so the animation starts on the first frame
+        let now = system.current_time_milliseconds();
+        let delta = self
+            .last_tick
+            .map(|last| now.wrapping_sub(last))
+            .unwrap_or(0);
+        self.last_tick = Some(now);
+        self.advance(delta)
+    }
+
+    pub fn frame(&self) -> Option<Rc<Bitmap>> {
+        self.frames.get(self.frame_index()).cloned()
+    }
+
+    pub fn frame_index(&self) -> usize {
+        let last = self.frames.len().saturating_sub(1);
+        let total = self.duration();
+        if total == 0 {
+            return 0;
+        }
+
+        if self.is_finished() {
+            return last;
+        }
+
+        let mut time = self.elapsed % total;
+
+        // The way back skips the last and first frames, which the way forwards shows
+        let ping_pong = self.mode == AnimationMode::PingPong;
+        let backwards = (1..last).rev().filter(|_| ping_pong);
+
+        for index in (0..=last).chain(backwards) {
+            if time < self.durations[index] {
+                return index;
+            }
+
+            time -= self.durations[index];
+        }
+
+        last
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.mode == AnimationMode::Once && self.elapsed >= self.duration()
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+        self.last_tick = None;
+    }
+
+    pub fn elapsed(&self) -> u32 {
+        self.elapsed
+    }
+
+    // The length of one cycle, which for ping-pong covers both directions
+    pub fn duration(&self) -> u32 {
+        let forwards: u32 = self.durations.iter().sum();
+
+        match self.mode {
+            AnimationMode::PingPong if self.durations.len() > 2 => {
+                let inner = &self.durations[1..self.durations.len() - 1];
+                forwards + inner.iter().sum::<u32>()
+            }
+            _ => forwards,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn frames(&self) -> &[Rc<Bitmap>] {
+        &self.frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{mem, ptr::null_mut};
+
+    // Frame selection doesn't look at the bitmaps, so every frame shares one that is
+    // never freed, since freeing goes through the SDK
+    fn animation(len: usize, frame_duration: u32, mode: AnimationMode) -> Animation {
+        let bitmap = Rc::new(Bitmap {
+            ptr: null_mut(),
+            mask: None,
+        });
+        mem::forget(bitmap.clone());
+        Animation::new(vec![bitmap; len], frame_duration).mode(mode)
+    }
+
+    // The frame shown at each multiple of `step` from 0 up to and including `end`
+    fn frames(animation: &mut Animation, step: u32, end: u32) -> Vec<usize> {
+        animation.reset();
+        let mut frames = vec![animation.frame_index()];
+        while animation.elapsed() < end {
+            animation.advance(step);
+            frames.push(animation.frame_index());
+        }
+        frames
+    }
+
+    #[test]
+    fn plays_once() {
+        let mut animation = animation(3, 100, AnimationMode::Once);
+        assert_eq!(animation.duration(), 300);
+        assert_eq!(frames(&mut animation, 50, 400), [0, 0, 1, 1, 2, 2, 2, 2, 2]);
+        assert!(animation.is_finished());
+
+        animation.reset();
+        animation.advance(299);
+        assert!(!animation.is_finished());
+    }
+
+    #[test]
+    fn loops() {
+        let mut animation = animation(3, 100, AnimationMode::Loop);
+        assert_eq!(frames(&mut animation, 100, 700), [0, 1, 2, 0, 1, 2, 0, 1]);
+        assert!(!animation.is_finished());
+    }
+
+    #[test]
+    fn ping_pongs_without_repeating_the_ends() {
+        let mut animation = animation(4, 100, AnimationMode::PingPong);
+        assert_eq!(animation.duration(), 600);
+        assert_eq!(
+            frames(&mut animation, 100, 1200),
+            [0, 1, 2, 3, 2, 1, 0, 1, 2, 3, 2, 1, 0]
+        );
+
+        let mut animation = self::animation(2, 100, AnimationMode::PingPong);
+        assert_eq!(animation.duration(), 200);
+        assert_eq!(frames(&mut animation, 100, 400), [0, 1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn uses_per_frame_durations() {
+        let mut animation = animation(3, 100, AnimationMode::Loop).durations(&[50, 150]);
+        assert_eq!(animation.duration(), 300);
+        assert_eq!(frames(&mut animation, 50, 300), [0, 1, 1, 1, 2, 2, 0]);
+
+        let mut animation = self::animation(3, 100, AnimationMode::PingPong)
+            .frame_duration(1, 200)
+            .frame_duration(5, 1000);
+        assert_eq!(animation.duration(), 600);
+        assert_eq!(frames(&mut animation, 100, 600), [0, 1, 1, 2, 1, 1, 0]);
+    }
+
+    #[test]
+    fn single_and_empty_animations() {
+        for mode in [
+            AnimationMode::Once,
+            AnimationMode::Loop,
+            AnimationMode::PingPong,
+        ] {
+            let mut animation = animation(1, 100, mode);
+            assert_eq!(animation.duration(), 100);
+            assert_eq!(frames(&mut animation, 50, 300), [0; 7]);
+
+            let mut animation = self::animation(0, 100, mode);
+            assert!(animation.is_empty());
+            assert!(animation.advance(100).is_none());
+            assert_eq!(animation.frame_index(), 0);
+        }
+
+        let mut animation = animation(3, 0, AnimationMode::Loop);
+        assert!(animation.advance(100).is_some());
+        assert_eq!(animation.frame_index(), 0);
+    }
+}
@@ -1,20 +1,26 @@
 use crate::{
     error::{Error, Result},
-    gfx::{Color, IntRect},
+    geometry::{IntPoint, IntRect, IntSize},
+    gfx::{Color, Graphics},
     libc,
+    pixels::{Layout, Pixels, PixelsMut},
+    sprite::DrawMode,
 };
 use alloc::{borrow::ToOwned, rc::Rc};
 use core::{
     ffi::CStr,
+    marker::PhantomData,
     mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
     ptr::{null, null_mut},
+    slice,
 };
 use playdate_sys::{
     LCDBitmap, LCDBitmapFlip_kBitmapFlippedX, LCDBitmapFlip_kBitmapFlippedXY,
     LCDBitmapFlip_kBitmapFlippedY, LCDBitmapFlip_kBitmapUnflipped, LCDBitmapTable,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct Bitmap {
     pub(crate) ptr: *mut LCDBitmap,
     pub(crate) mask: Option<Rc<Bitmap>>,
@@ -22,7 +28,7 @@ pub struct Bitmap {
 
 impl Bitmap {
     pub(crate) fn new(width: i32, height: i32, bg_color: Color) -> Self {
-        let ptr = invoke_unsafe!(graphics.newBitmap, width, height, bg_color as _);
+        let ptr = invoke_unsafe!(graphics.newBitmap, width, height, bg_color.as_lcd_color());
         let mask = None;
         Self { ptr, mask }
     }
@@ -56,6 +62,13 @@ impl Bitmap {
         Self { ptr, mask }
     }
 
+    // A deep copy, including the mask
+    pub fn duplicate(&self) -> Self {
+        let ptr = invoke_unsafe!(graphics.copyBitmap, self.ptr);
+        let mask = None;
+        Self { ptr, mask }
+    }
+
     pub fn load_from(&mut self, path: &CStr) -> Result<()> {
         let err = null_mut();
         invoke_unsafe!(graphics.loadIntoBitmap, path.as_ptr(), self.ptr, err);
@@ -86,8 +99,41 @@ impl Bitmap {
         }
     }
 
+    // The mask shares its data with this bitmap, so drawing into it through `mask_mut()`
+    // changes which pixels are opaque
+    pub fn mask(&self) -> Option<BitmapMask<'_>> {
+        let ptr = invoke_unsafe!(graphics.getBitmapMask, self.ptr);
+        (!ptr.is_null()).then(|| BitmapMask::new(ptr))
+    }
+
+    pub fn mask_mut(&mut self) -> Option<BitmapMaskMut<'_>> {
+        let ptr = invoke_unsafe!(graphics.getBitmapMask, self.ptr);
+        (!ptr.is_null()).then(|| BitmapMaskMut(BitmapMask::new(ptr)))
+    }
+
+    // An independent copy of the mask
+    pub fn copy_mask(&self) -> Option<Bitmap> {
+        self.mask().map(|mask| mask.duplicate())
+    }
+
     pub fn clear(&mut self, color: Color) {
-        invoke_unsafe!(graphics.clearBitmap, self.ptr, color as _)
+        invoke_unsafe!(graphics.clearBitmap, self.ptr, color.as_lcd_color())
+    }
+
+    // Copies the `src_rect` part of `src` into this bitmap with its top left corner at `dst`
+    pub fn blit(&mut self, src: &Bitmap, src_rect: IntRect, dst: IntPoint, mode: DrawMode) {
+        let mut gfx = Graphics::new();
+        gfx.with_context(self, |gfx| {
+            gfx.set_stencil(None);
+            gfx.set_draw_offset(0, 0);
+            gfx.set_draw_mode(mode);
+            gfx.set_clip_rect(IntRect::from_origin_size(dst, src_rect.size()));
+            src.draw(
+                dst.x - src_rect.x,
+                dst.y - src_rect.y,
+                BitmapFlip::Unflipped,
+            );
+        })
     }
 
     pub fn check_mask_collision(
@@ -111,7 +157,7 @@ impl Bitmap {
             other_x,
             other_y,
             other_flip as _,
-            rect
+            rect.into()
         );
         return result == 1;
     }
@@ -173,21 +219,80 @@ impl Bitmap {
         }
     }
 
+    pub fn has_mask(&self) -> bool {
+        !self.data().mask.is_null()
+    }
+
+    pub fn pixels(&self) -> Pixels<'_> {
+        let data = self.data();
+        let layout = data.layout();
+        let pixels = unsafe { slice::from_raw_parts(data.data, layout.len()) };
+        let mask = (!data.mask.is_null())
+            .then(|| unsafe { slice::from_raw_parts(data.mask as *const u8, layout.len()) });
+
+        Pixels::new(layout, pixels, mask)
+    }
+
+    pub fn pixels_mut(&mut self) -> PixelsMut<'_> {
+        let data = self.data();
+        let layout = data.layout();
+        let pixels = unsafe { slice::from_raw_parts_mut(data.data, layout.len()) };
+        let mask = (!data.mask.is_null())
+            .then(|| unsafe { slice::from_raw_parts_mut(data.mask, layout.len()) });
+
+        PixelsMut::new(layout, pixels, mask)
+    }
+
     pub(crate) fn as_mut_ptr(&self) -> *mut LCDBitmap {
         self.ptr
     }
 }
 
+// The mask is freed by its own `Bitmap` once the last `Rc` to it is dropped
 impl Drop for Bitmap {
     fn drop(&mut self) {
-        if let Some(mask) = self.mask.as_ref() {
-            invoke_unsafe!(graphics.freeBitmap, mask.as_mut_ptr());
-        }
-
         invoke_unsafe!(graphics.freeBitmap, self.ptr)
     }
 }
 
+pub struct BitmapMask<'a> {
+    bitmap: Bitmap,
+    _source: PhantomData<&'a Bitmap>,
+}
+
+impl BitmapMask<'_> {
+    fn new(ptr: *mut LCDBitmap) -> Self {
+        let mask = None;
+        let bitmap = Bitmap { ptr, mask };
+        let _source = PhantomData;
+        Self { bitmap, _source }
+    }
+}
+
+impl Deref for BitmapMask<'_> {
+    type Target = Bitmap;
+
+    fn deref(&self) -> &Self::Target {
+        &self.bitmap
+    }
+}
+
+pub struct BitmapMaskMut<'a>(BitmapMask<'a>);
+
+impl Deref for BitmapMaskMut<'_> {
+    type Target = Bitmap;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0.bitmap
+    }
+}
+
+impl DerefMut for BitmapMaskMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0.bitmap
+    }
+}
+
 pub struct BitmapData {
     pub width: i32,
     pub height: i32,
@@ -196,6 +301,16 @@ pub struct BitmapData {
     pub data: *mut u8,
 }
 
+impl BitmapData {
+    fn layout(&self) -> Layout {
+        Layout {
+            width: self.width,
+            height: self.height,
+            row_bytes: self.row_bytes as usize,
+        }
+    }
+}
+
 pub struct BitmapTable(*mut LCDBitmapTable);
 
 impl BitmapTable {
@@ -237,6 +352,51 @@ impl BitmapTable {
         Err(Error { message })
     }
 
+    pub fn len(&self) -> usize {
+        let mut count = 0;
+        let mut width = 0;
+        invoke_unsafe!(graphics.getBitmapTableInfo, self.0, &mut count, &mut width);
+        count.max(0) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // All frames in a table share the size of the first one
+    pub fn frame_size(&self) -> Option<IntSize> {
+        self.get(0).map(|frame| {
+            let data = frame.data();
+            IntSize {
+                width: data.width,
+                height: data.height,
+            }
+        })
+    }
+
+    // The frame is owned by the table, so it can't outlive it. Use `Bitmap::duplicate` to
+    // keep a copy.
+    pub fn get(&self, index: usize) -> Option<TableBitmap<'_>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        self.frame(index)
+    }
+
+    pub fn iter(&self) -> BitmapTableIter<'_> {
+        BitmapTableIter {
+            table: self,
+            index: 0,
+            len: self.len(),
+        }
+    }
+
+    fn frame(&self, index: usize) -> Option<TableBitmap<'_>> {
+        let ptr = invoke_unsafe!(graphics.getTableBitmap, self.0, index as _);
+        (!ptr.is_null()).then(|| TableBitmap::new(ptr))
+    }
+
     pub fn with_bitmap<F>(&mut self, index: i32, mut f: F) -> bool
     where
         F: FnMut(&mut Bitmap),
@@ -261,6 +421,64 @@ impl Drop for BitmapTable {
     }
 }
 
+impl<'a> IntoIterator for &'a BitmapTable {
+    type Item = TableBitmap<'a>;
+    type IntoIter = BitmapTableIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct TableBitmap<'a> {
+    bitmap: ManuallyDrop<Bitmap>,
+    _table: PhantomData<&'a BitmapTable>,
+}
+
+impl TableBitmap<'_> {
+    fn new(ptr: *mut LCDBitmap) -> Self {
+        let mask = None;
+        let bitmap = ManuallyDrop::new(Bitmap { ptr, mask });
+        let _table = PhantomData;
+        Self { bitmap, _table }
+    }
+}
+
+impl Deref for TableBitmap<'_> {
+    type Target = Bitmap;
+
+    fn deref(&self) -> &Self::Target {
+        &self.bitmap
+    }
+}
+
+pub struct BitmapTableIter<'a> {
+    table: &'a BitmapTable,
+    index: usize,
+    len: usize,
+}
+
+impl<'a> Iterator for BitmapTableIter<'a> {
+    type Item = TableBitmap<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            return None;
+        }
+
+        let frame = self.table.frame(self.index);
+        self.index += 1;
+        frame
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for BitmapTableIter<'_> {}
+
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum BitmapFlip {
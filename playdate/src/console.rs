@@ -0,0 +1,267 @@
+use crate::{
+    error::{Error, Result},
+    geometry::Rect,
+    gfx::{Color, Graphics},
+    libc,
+    memory::memory_stats,
+    rng,
+    system::System,
+};
+use alloc::{
+    boxed::Box,
+    ffi::CString,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    ffi::{c_char, CStr},
+    ptr::addr_of_mut,
+    str::{FromStr, SplitWhitespace},
+};
+
+type Handler = Box<dyn FnMut(&mut Args) -> Result<String>>;
+
+struct Command {
+    name: String,
+    help: String,
+    handler: Handler,
+}
+
+// The command whose handler is running, which is out of the registry until it returns
+struct Running {
+    name: String,
+    unregistered: bool,
+}
+
+struct Registry {
+    commands: Vec<Command>,
+    running: Option<Running>,
+    debug_draw: bool,
+}
+
+// Serial messages arrive outside of any `Playdate` instance, so the commands live in a
+// static just like the task executor does
+static mut REGISTRY: Registry = Registry {
+    commands: Vec::new(),
+    running: None,
+    debug_draw: false,
+};
+
+fn registry() -> &'static mut Registry {
+    unsafe { &mut *addr_of_mut!(REGISTRY) }
+}
+
+extern "C" fn serial_message_callback(data: *const c_char) {
+    let line = unsafe { CStr::from_ptr(data) }.to_string_lossy();
+    run(&line);
+}
+
+fn log(message: &str) {
+    let system = System::new();
+
+    for line in message.lines() {
+        let line = CString::new(line).unwrap_or_default();
+        system.log_to_console(&line);
+    }
+}
+
+fn run(line: &str) {
+    let mut words = line.split_whitespace();
+    let Some(name) = words.next() else {
+        return;
+    };
+
+    let commands = &mut registry().commands;
+    let response = match commands.iter().position(|command| command.name == name) {
+        Some(index) => {
+            // Handlers may register and unregister commands, including their own, so the
+            // registry can't stay borrowed while one runs
+            let mut command = commands.remove(index);
+            registry().running = Some(Running {
+                name: command.name.clone(),
+                unregistered: false,
+            });
+
+            let response = (command.handler)(&mut Args { words });
+
+            // A command registered under the same name replaces the running one
+            let running = registry().running.take();
+            let commands = &mut registry().commands;
+            let replaced = commands.iter().any(|c| c.name == command.name);
+            if !replaced && !running.is_some_and(|running| running.unregistered) {
+                commands.insert(index.min(commands.len()), command);
+            }
+
+            response
+        }
+        None if name == "help" => Ok(help(commands)),
+        None => Err(Error::new(format!(
+            "unknown command '{}', try 'help'",
+            name
+        ))),
+    };
+
+    match response {
+        Ok(response) => log(&response),
+        Err(err) => log(&format!("error: {}", err.message.to_string_lossy())),
+    }
+}
+
+fn help(commands: &[Command]) -> String {
+    let mut out = String::from("commands:");
+
+    for command in commands {
+        out.push_str(&format!("\n  {} - {}", command.name, command.help));
+    }
+
+    out
+}
+
+fn insert(command: Command) {
+    let commands = &mut registry().commands;
+
+    match commands.iter_mut().find(|c| c.name == command.name) {
+        Some(existing) => *existing = command,
+        None => commands.push(command),
+    }
+}
+
+pub struct Console {
+    _unused: [u8; 0],
+}
+
+impl Console {
+    pub(crate) fn new() -> Self {
+        let _unused = Default::default();
+        Self { _unused }
+    }
+
+    // Registering a command with an existing name replaces it
+    pub fn register<C>(&mut self, name: &str, help: &str, handler: C)
+    where
+        C: FnMut(&mut Args) -> Result<String> + 'static,
+    {
+        insert(Command {
+            name: name.to_string(),
+            help: help.to_string(),
+            handler: Box::new(handler),
+        });
+
+        let mut system = System::new();
+        unsafe { system.set_serial_message_callback(serial_message_callback) };
+    }
+
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let registry = registry();
+        let running = registry
+            .running
+            .as_mut()
+            .filter(|running| running.name == name && !running.unregistered)
+            .map(|running| running.unregistered = true)
+            .is_some();
+
+        let len = registry.commands.len();
+        registry.commands.retain(|command| command.name != name);
+        running || registry.commands.len() != len
+    }
+
+    pub fn register_builtins(&mut self) {
+        self.register("sprites", "number of sprites in the display list", |_| {
+            let count = invoke_unsafe!(sprite.getSpriteCount);
+            Ok(format!("{} sprites", count))
+        });
+
+        self.register("mem", "heap memory allocated from Rust", |_| {
+            let stats = memory_stats();
+            Ok(format!(
+                "allocated: {} bytes, peak: {} bytes, allocations: {}",
+                stats.allocated, stats.peak, stats.allocations
+            ))
+        });
+
+        self.register("debugdraw", "[on|off] outline sprite bounds", |args| {
+            let enabled = match args.word() {
+                Ok("on") => true,
+                Ok("off") => false,
+                Ok(other) => Err(Error::new(format!("expected on or off, got '{}'", other)))?,
+                Err(_) => !debug_draw_enabled(),
+            };
+
+            set_debug_draw(enabled);
+            Ok(format!("debug draw {}", if enabled { "on" } else { "off" }))
+        });
+
+        self.register("seed", "<n> seed the random number generator", |args| {
+            let seed = args.parse()?;
+            rng::set_seed(seed);
+            Ok(format!("seed set to {}", seed))
+        });
+    }
+
+    // Runs a command line as if it had arrived over serial
+    pub fn run(&mut self, line: &str) {
+        run(line)
+    }
+
+    pub fn log(&self, message: &str) {
+        log(message)
+    }
+}
+
+pub fn debug_draw_enabled() -> bool {
+    registry().debug_draw
+}
+
+pub fn set_debug_draw(enabled: bool) {
+    registry().debug_draw = enabled;
+}
+
+pub(crate) fn draw_debug(gfx: &mut Graphics) {
+    if !debug_draw_enabled() {
+        return;
+    }
+
+    let mut len = 0;
+    let ptr = invoke_unsafe!(sprite.querySpritesInRect, 0.0, 0.0, 400.0, 240.0, &mut len);
+    if ptr.is_null() {
+        return;
+    }
+
+    let sprites = unsafe { core::slice::from_raw_parts(ptr, len as usize) };
+    for &sprite in sprites {
+        let bounds = Rect::from(invoke_unsafe!(sprite.getBounds, sprite));
+        gfx.draw_rect(bounds.round_out(), Color::Xor);
+    }
+
+    unsafe { libc::free(ptr as _) };
+}
+
+pub struct Args<'a> {
+    words: SplitWhitespace<'a>,
+}
+
+impl<'a> Args<'a> {
+    pub fn word(&mut self) -> Result<&'a str> {
+        self.words
+            .next()
+            .ok_or_else(|| Error::new("missing argument"))
+    }
+
+    pub fn parse<T: FromStr>(&mut self) -> Result<T> {
+        let word = self.word()?;
+        word.parse()
+            .map_err(|_| Error::new(format!("invalid argument '{}'", word)))
+    }
+
+    pub fn parse_or<T: FromStr>(&mut self, default: T) -> Result<T> {
+        match self.words.clone().next() {
+            Some(_) => self.parse(),
+            None => Ok(default),
+        }
+    }
+
+    pub fn rest(&mut self) -> Vec<&'a str> {
+        self.words.by_ref().collect()
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    bitmap::Bitmap,
+    font::Font,
+    geometry::IntRect,
+    gfx::{Graphics, LineCapStyle},
+    sprite::{DrawMode, TileMode},
+};
+use core::{
+    mem,
+    ops::{Deref, DerefMut},
+    ptr::{addr_of_mut, null_mut},
+};
+use playdate_sys::{LCDBitmap, LCDFont};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ClipRect {
+    None,
+    World(IntRect),
+    Screen(IntRect),
+}
+
+// The SDK has no getters for most of its drawing state, so the last value set through
+// `Graphics` is tracked here in order to restore it later
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct DrawState {
+    pub(crate) draw_mode: DrawMode,
+    pub(crate) clip_rect: ClipRect,
+    pub(crate) draw_offset: (i32, i32),
+    pub(crate) line_cap_style: LineCapStyle,
+    pub(crate) tracking: i32,
+    pub(crate) leading: i32,
+    pub(crate) font: *mut LCDFont,
+    pub(crate) stencil: (*mut LCDBitmap, TileMode),
+}
+
+static mut DRAW_STATE: DrawState = DrawState {
+    draw_mode: DrawMode::Copy,
+    clip_rect: ClipRect::None,
+    draw_offset: (0, 0),
+    line_cap_style: LineCapStyle::Butt,
+    tracking: 0,
+    leading: 0,
+    font: null_mut(),
+    stencil: (null_mut(), TileMode::NoTile),
+};
+
+pub(crate) fn draw_state() -> &'static mut DrawState {
+    unsafe { &mut *addr_of_mut!(DRAW_STATE) }
+}
+
+impl DrawState {
+    fn restore(&self, current: &DrawState) {
+        if self.draw_mode != current.draw_mode {
+            invoke_unsafe!(graphics.setDrawMode, self.draw_mode as _);
+        }
+
+        if self.clip_rect != current.clip_rect {
+            match self.clip_rect {
+                ClipRect::None => invoke_unsafe!(graphics.clearClipRect),
+                ClipRect::World(rect) => invoke_unsafe!(
+                    graphics.setClipRect,
+                    rect.x,
+                    rect.y,
+                    rect.width,
+                    rect.height
+                ),
+                ClipRect::Screen(rect) => invoke_unsafe!(
+                    graphics.setScreenClipRect,
+                    rect.x,
+                    rect.y,
+                    rect.width,
+                    rect.height
+                ),
+            }
+        }
+
+        if self.draw_offset != current.draw_offset {
+            let (dx, dy) = self.draw_offset;
+            invoke_unsafe!(graphics.setDrawOffset, dx, dy);
+        }
+
+        if self.line_cap_style != current.line_cap_style {
+            invoke_unsafe!(graphics.setLineCapStyle, self.line_cap_style as _);
+        }
+
+        if self.tracking != current.tracking {
+            invoke_unsafe!(graphics.setTextTracking, self.tracking);
+        }
+
+        if self.leading != current.leading {
+            invoke_unsafe!(graphics.setTextLeading, self.leading);
+        }
+
+        if self.font != current.font {
+            invoke_unsafe!(graphics.setFont, self.font);
+        }
+
+        if self.stencil != current.stencil {
+            match self.stencil {
+                (stencil, _) if stencil.is_null() => invoke_unsafe!(graphics.setStencil, stencil),
+                (stencil, tile) => invoke_unsafe!(graphics.setStencilImage, stencil, tile as _),
+            }
+        }
+    }
+}
+
+// The font and stencil a guard started with, once something inside it replaces them. The
+// guard puts them back when it drops, so they're kept alive here rather than handed back
+// to the caller.
+#[derive(Default)]
+pub(crate) struct ScopedState {
+    pub(crate) font: Option<Option<Font>>,
+    pub(crate) stencil: Option<Option<(Bitmap, TileMode)>>,
+}
+
+// Scopes the drawing state of `Graphics`: everything set through the guard is put back the
+// way it was when the guard drops, including the font and stencil. Fonts and stencils set
+// before the guard was created stay readable on `Graphics`, and are kept alive until the
+// guard drops even if they're replaced inside it.
+pub struct ContextGuard<'a> {
+    gfx: &'a mut Graphics,
+    state: DrawState,
+    pushed: bool,
+}
+
+impl<'a> ContextGuard<'a> {
+    pub(crate) fn new(gfx: &'a mut Graphics, target: Option<&mut Bitmap>) -> Self {
+        let state = *draw_state();
+        gfx.scopes.push(ScopedState::default());
+
+        // Pushing a context copies the current state into it, which popContext() restores
+        let pushed = target.is_some();
+        if let Some(target) = target {
+            invoke_unsafe!(graphics.pushContext, target.as_mut_ptr());
+        }
+
+        Self { gfx, state, pushed }
+    }
+}
+
+impl Deref for ContextGuard<'_> {
+    type Target = Graphics;
+
+    fn deref(&self) -> &Self::Target {
+        self.gfx
+    }
+}
+
+impl DerefMut for ContextGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.gfx
+    }
+}
+
+impl Drop for ContextGuard<'_> {
+    fn drop(&mut self) {
+        if self.pushed {
+            invoke_unsafe!(graphics.popContext);
+        } else {
+            self.state.restore(draw_state());
+        }
+
+        *draw_state() = self.state;
+
+        // Only drop whatever was set inside the guard once the SDK no longer points at it
+        let scoped = self.gfx.scopes.pop().unwrap_or_default();
+        if let Some(font) = scoped.font {
+            drop(mem::replace(&mut self.gfx.font, font));
+        }
+
+        if let Some(stencil) = scoped.stencil {
+            drop(mem::replace(&mut self.gfx.stencil, stencil));
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::pattern::BAYER_8X8;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DitherType {
+    // A plain threshold at 50%
+    None,
+    Bayer2x2,
+    Bayer4x4,
+    Bayer8x8,
+    #[default]
+    FloydSteinberg,
+    Atkinson,
+}
+
+// (dx, dy, weight) of the neighbours that receive a share of each pixel's error
+const FLOYD_STEINBERG: &[(isize, usize, i16)] = &[(1, 0, 7), (-1, 1, 3), (0, 1, 5), (1, 1, 1)];
+const FLOYD_STEINBERG_DIVISOR: i16 = 16;
+
+// Atkinson only passes on 6/8 of the error, which keeps more contrast
+const ATKINSON: &[(isize, usize, i16)] = &[
+    (1, 0, 1),
+    (2, 0, 1),
+    (-1, 1, 1),
+    (0, 1, 1),
+    (1, 1, 1),
+    (0, 2, 1),
+];
+const ATKINSON_DIVISOR: i16 = 8;
+
+// Converts greyscale `values`, `width` to a row with 0 being black and 255 white, to 1 bit.
+// `set` is called with whether each pixel is white. The values are used as scratch space
+// for error diffusion.
+pub(crate) fn dither<F>(values: &mut [i16], width: usize, dither_type: DitherType, set: F)
+where
+    F: FnMut(i32, i32, bool),
+{
+    // Zero width images have no pixels, and would otherwise divide by zero
+    if width == 0 {
+        return;
+    }
+
+    match dither_type {
+        DitherType::None => threshold(values, width, set, |_, _| 128),
+        DitherType::Bayer2x2 => bayer(values, width, 4, set),
+        DitherType::Bayer4x4 => bayer(values, width, 2, set),
+        DitherType::Bayer8x8 => bayer(values, width, 0, set),
+        DitherType::FloydSteinberg => {
+            diffuse(values, width, FLOYD_STEINBERG, FLOYD_STEINBERG_DIVISOR, set)
+        }
+        DitherType::Atkinson => diffuse(values, width, ATKINSON, ATKINSON_DIVISOR, set),
+    }
+}
+
+fn threshold<F, T>(values: &[i16], width: usize, mut set: F, threshold: T)
+where
+    F: FnMut(i32, i32, bool),
+    T: Fn(usize, usize) -> i16,
+{
+    for (i, &value) in values.iter().enumerate() {
+        let (x, y) = (i % width, i / width);
+        set(x as i32, y as i32, value >= threshold(x, y));
+    }
+}
+
+// Uses the top left corner of the 8x8 matrix, shifted down to the smaller matrix's levels
+fn bayer<F>(values: &[i16], width: usize, shift: u32, set: F)
+where
+    F: FnMut(i32, i32, bool),
+{
+    let levels = 64 >> shift;
+    let size = 8 >> (shift / 2);
+
+    threshold(values, width, set, |x, y| {
+        let level = (BAYER_8X8[y % size][x % size] >> shift) as i16;
+        (level * 2 + 1) * 255 / (levels * 2)
+    })
+}
+
+fn diffuse<F>(
+    values: &mut [i16],
+    width: usize,
+    kernel: &[(isize, usize, i16)],
+    divisor: i16,
+    mut set: F,
+) where
+    F: FnMut(i32, i32, bool),
+{
+    let height = values.len() / width;
+
+    for y in 0..height {
+        for x in 0..width {
+            let value = values[y * width + x];
+            let white = value >= 128;
+            set(x as i32, y as i32, white);
+
+            let error = value - if white { 255 } else { 0 };
+            for &(dx, dy, weight) in kernel {
+                let nx = x as isize + dx;
+                let ny = y + dy;
+                if nx < 0 || nx as usize >= width || ny >= height {
+                    continue;
+                }
+
+                values[ny * width + nx as usize] += error * weight / divisor;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    const TYPES: [DitherType; 6] = [
+        DitherType::None,
+        DitherType::Bayer2x2,
+        DitherType::Bayer4x4,
+        DitherType::Bayer8x8,
+        DitherType::FloydSteinberg,
+        DitherType::Atkinson,
+    ];
+
+    fn run(values: &[i16], width: usize, dither_type: DitherType) -> Vec<(i32, i32, bool)> {
+        let mut values = values.to_vec();
+        let mut out = Vec::new();
+        dither(&mut values, width, dither_type, |x, y, white| {
+            out.push((x, y, white))
+        });
+        out
+    }
+
+    #[test]
+    fn empty_images_set_nothing() {
+        for dither_type in TYPES {
+            assert!(run(&[], 0, dither_type).is_empty());
+            assert!(run(&[], 4, dither_type).is_empty());
+        }
+    }
+
+    #[test]
+    fn keeps_black_and_white() {
+        for dither_type in TYPES {
+            let out = run(&[0, 255, 0, 255], 2, dither_type);
+            let whites: Vec<_> = out.iter().map(|&(_, _, white)| white).collect();
+            assert_eq!(whites, [false, true, false, true], "{:?}", dither_type);
+            assert_eq!(out[3], (1, 1, true));
+        }
+    }
+
+    #[test]
+    fn mid_grey_is_half_white() {
+        for dither_type in [DitherType::Bayer4x4, DitherType::FloydSteinberg] {
+            let whites = run(&[128; 64], 8, dither_type)
+                .iter()
+                .filter(|&&(_, _, white)| white)
+                .count();
+            assert!((24..=40).contains(&whites), "{:?}: {}", dither_type, whites);
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use crate::math::{cos, pow, sin, sqrt};
+use core::f32::consts::PI;
+
+// All curves take a normalized time in 0..=1 and return the eased progress, which is 0 at
+// the start and 1 at the end (elastic and back curves overshoot in between).
+
+const BACK_OVERSHOOT: f32 = 1.70158;
+const ELASTIC_PERIOD: f32 = 0.3;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    OutInQuad,
+    InCubic,
+    OutCubic,
+    InOutCubic,
+    OutInCubic,
+    InQuart,
+    OutQuart,
+    InOutQuart,
+    OutInQuart,
+    InQuint,
+    OutQuint,
+    InOutQuint,
+    OutInQuint,
+    InSine,
+    OutSine,
+    InOutSine,
+    OutInSine,
+    InExpo,
+    OutExpo,
+    InOutExpo,
+    OutInExpo,
+    InCirc,
+    OutCirc,
+    InOutCirc,
+    OutInCirc,
+    InElastic,
+    OutElastic,
+    InOutElastic,
+    OutInElastic,
+    InBack,
+    OutBack,
+    InOutBack,
+    OutInBack,
+    InBounce,
+    OutBounce,
+    InOutBounce,
+    OutInBounce,
+    Custom(fn(f32) -> f32),
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Self::Linear => linear(t),
+            Self::InQuad => in_quad(t),
+            Self::OutQuad => out_quad(t),
+            Self::InOutQuad => in_out_quad(t),
+            Self::OutInQuad => out_in_quad(t),
+            Self::InCubic => in_cubic(t),
+            Self::OutCubic => out_cubic(t),
+            Self::InOutCubic => in_out_cubic(t),
+            Self::OutInCubic => out_in_cubic(t),
+            Self::InQuart => in_quart(t),
+            Self::OutQuart => out_quart(t),
+            Self::InOutQuart => in_out_quart(t),
+            Self::OutInQuart => out_in_quart(t),
+            Self::InQuint => in_quint(t),
+            Self::OutQuint => out_quint(t),
+            Self::InOutQuint => in_out_quint(t),
+            Self::OutInQuint => out_in_quint(t),
+            Self::InSine => in_sine(t),
+            Self::OutSine => out_sine(t),
+            Self::InOutSine => in_out_sine(t),
+            Self::OutInSine => out_in_sine(t),
+            Self::InExpo => in_expo(t),
+            Self::OutExpo => out_expo(t),
+            Self::InOutExpo => in_out_expo(t),
+            Self::OutInExpo => out_in_expo(t),
+            Self::InCirc => in_circ(t),
+            Self::OutCirc => out_circ(t),
+            Self::InOutCirc => in_out_circ(t),
+            Self::OutInCirc => out_in_circ(t),
+            Self::InElastic => in_elastic(t),
+            Self::OutElastic => out_elastic(t),
+            Self::InOutElastic => in_out_elastic(t),
+            Self::OutInElastic => out_in_elastic(t),
+            Self::InBack => in_back(t),
+            Self::OutBack => out_back(t),
+            Self::InOutBack => in_out_back(t),
+            Self::OutInBack => out_in_back(t),
+            Self::InBounce => in_bounce(t),
+            Self::OutBounce => out_bounce(t),
+            Self::InOutBounce => in_out_bounce(t),
+            Self::OutInBounce => out_in_bounce(t),
+            Self::Custom(f) => f(t),
+        }
+    }
+}
+
+fn out_of(f: fn(f32) -> f32, t: f32) -> f32 {
+    1.0 - f(1.0 - t)
+}
+
+fn in_out_of(f: fn(f32) -> f32, t: f32) -> f32 {
+    if t < 0.5 {
+        f(t * 2.0) / 2.0
+    } else {
+        1.0 - f(2.0 - t * 2.0) / 2.0
+    }
+}
+
+fn out_in_of(f: fn(f32) -> f32, t: f32) -> f32 {
+    if t < 0.5 {
+        out_of(f, t * 2.0) / 2.0
+    } else {
+        0.5 + f(t * 2.0 - 1.0) / 2.0
+    }
+}
+
+// Each family is defined by its "in" curve; the other three variants are mirrored from it
+macro_rules! easing_family {
+    ( $in:ident, $out:ident, $in_out:ident, $out_in:ident ) => {
+        pub fn $out(t: f32) -> f32 {
+            out_of($in, t)
+        }
+
+        pub fn $in_out(t: f32) -> f32 {
+            in_out_of($in, t)
+        }
+
+        pub fn $out_in(t: f32) -> f32 {
+            out_in_of($in, t)
+        }
+    };
+}
+
+pub fn linear(t: f32) -> f32 {
+    t
+}
+
+pub fn in_quad(t: f32) -> f32 {
+    t * t
+}
+
+pub fn in_cubic(t: f32) -> f32 {
+    t * t * t
+}
+
+pub fn in_quart(t: f32) -> f32 {
+    t * t * t * t
+}
+
+pub fn in_quint(t: f32) -> f32 {
+    t * t * t * t * t
+}
+
+pub fn in_sine(t: f32) -> f32 {
+    1.0 - cos(t * PI / 2.0)
+}
+
+pub fn in_expo(t: f32) -> f32 {
+    if t <= 0.0 {
+        0.0
+    } else {
+        pow(2.0, 10.0 * (t - 1.0))
+    }
+}
+
+pub fn in_circ(t: f32) -> f32 {
+    1.0 - sqrt(1.0 - t * t)
+}
+
+pub fn in_elastic(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t.clamp(0.0, 1.0);
+    }
+
+    let s = ELASTIC_PERIOD / 4.0;
+    let t = t - 1.0;
+    -(pow(2.0, 10.0 * t) * sin((t - s) * (2.0 * PI) / ELASTIC_PERIOD))
+}
+
+pub fn in_back(t: f32) -> f32 {
+    t * t * ((BACK_OVERSHOOT + 1.0) * t - BACK_OVERSHOOT)
+}
+
+pub fn in_bounce(t: f32) -> f32 {
+    out_of(out_bounce, t)
+}
+
+pub fn out_bounce(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+pub fn in_out_bounce(t: f32) -> f32 {
+    in_out_of(in_bounce, t)
+}
+
+pub fn out_in_bounce(t: f32) -> f32 {
+    out_in_of(in_bounce, t)
+}
+
+easing_family!(in_quad, out_quad, in_out_quad, out_in_quad);
+easing_family!(in_cubic, out_cubic, in_out_cubic, out_in_cubic);
+easing_family!(in_quart, out_quart, in_out_quart, out_in_quart);
+easing_family!(in_quint, out_quint, in_out_quint, out_in_quint);
+easing_family!(in_sine, out_sine, in_out_sine, out_in_sine);
+easing_family!(in_expo, out_expo, in_out_expo, out_in_expo);
+easing_family!(in_circ, out_circ, in_out_circ, out_in_circ);
+easing_family!(in_elastic, out_elastic, in_out_elastic, out_in_elastic);
+easing_family!(in_back, out_back, in_out_back, out_in_back);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURVES: [Easing; 41] = [
+        Easing::Linear,
+        Easing::InQuad,
+        Easing::OutQuad,
+        Easing::InOutQuad,
+        Easing::OutInQuad,
+        Easing::InCubic,
+        Easing::OutCubic,
+        Easing::InOutCubic,
+        Easing::OutInCubic,
+        Easing::InQuart,
+        Easing::OutQuart,
+        Easing::InOutQuart,
+        Easing::OutInQuart,
+        Easing::InQuint,
+        Easing::OutQuint,
+        Easing::InOutQuint,
+        Easing::OutInQuint,
+        Easing::InSine,
+        Easing::OutSine,
+        Easing::InOutSine,
+        Easing::OutInSine,
+        Easing::InExpo,
+        Easing::OutExpo,
+        Easing::InOutExpo,
+        Easing::OutInExpo,
+        Easing::InCirc,
+        Easing::OutCirc,
+        Easing::InOutCirc,
+        Easing::OutInCirc,
+        Easing::InElastic,
+        Easing::OutElastic,
+        Easing::InOutElastic,
+        Easing::OutInElastic,
+        Easing::InBack,
+        Easing::OutBack,
+        Easing::InOutBack,
+        Easing::OutInBack,
+        Easing::InBounce,
+        Easing::OutBounce,
+        Easing::InOutBounce,
+        Easing::OutInBounce,
+    ];
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn curves_start_at_0_and_end_at_1() {
+        for easing in CURVES {
+            assert!(close(easing.apply(0.0), 0.0), "{:?} at 0", easing);
+            assert!(close(easing.apply(1.0), 1.0), "{:?} at 1", easing);
+        }
+    }
+
+    #[test]
+    fn symmetric_curves_meet_in_the_middle() {
+        for easing in [
+            Easing::Linear,
+            Easing::InOutQuad,
+            Easing::InOutCubic,
+            Easing::InOutSine,
+        ] {
+            assert!(close(easing.apply(0.5), 0.5), "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn clamps_time() {
+        assert_eq!(Easing::InQuad.apply(-1.0), 0.0);
+        assert_eq!(Easing::InQuad.apply(2.0), 1.0);
+        assert!(close(Easing::InQuad.apply(0.5), 0.25));
+        assert!(close(Easing::OutQuad.apply(0.5), 0.75));
+        assert_eq!(Easing::Custom(|t| t * t * t).apply(0.5), 0.125);
+    }
+
+    #[test]
+    fn back_overshoots() {
+        assert!(Easing::InBack.apply(0.2) < 0.0);
+        assert!(Easing::OutBack.apply(0.8) > 1.0);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    bitmap::Bitmap,
+    dither::{dither, DitherType},
+    gfx::{Color, SolidColor},
+    rng,
+};
+use alloc::{vec, vec::Vec};
+
+// Effects work on greyscale copies of the pixel data, with 0 black and 255 white, which
+// are dithered back down to 1 bit. Unless stated otherwise the mask is copied unchanged.
+impl Bitmap {
+    pub fn blurred(&self, radius: i32, passes: u32, dither_type: DitherType) -> Bitmap {
+        let (mut values, width, height) = self.greyscale();
+        let radius = radius.max(0) as usize;
+
+        for _ in 0..passes {
+            box_blur(&mut values, width, height, radius);
+        }
+
+        let mut out = self.duplicate();
+        out.set_greyscale(&mut values, width, dither_type);
+        out
+    }
+
+    // Fades the image towards transparent by dithering its mask, with `alpha` from 0.0
+    // (invisible) to 1.0 (unchanged)
+    pub fn faded(&self, alpha: f32, dither_type: DitherType) -> Bitmap {
+        let pixels = self.pixels();
+        let (width, height) = (pixels.width(), pixels.height());
+        let alpha = alpha.clamp(0.0, 1.0);
+
+        let mut values: Vec<i16> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| match pixels.get(x, y) {
+                Some(SolidColor::Clear) | None => 0,
+                _ => (alpha * 255.0) as i16,
+            })
+            .collect();
+
+        // Clear bitmaps are created with a mask
+        let mut out = Bitmap::new(width, height, Color::Clear);
+        let mut out_pixels = out.pixels_mut();
+        for (dst, src) in out_pixels.rows_mut().zip(pixels.rows()) {
+            dst.copy_from_slice(src);
+        }
+
+        if let Some(mut mask) = out_pixels.mask_mut() {
+            dither(&mut values, width as usize, dither_type, |x, y, opaque| {
+                mask.set(x, y, opaque);
+            });
+        }
+
+        out
+    }
+
+    pub fn inverted(&self) -> Bitmap {
+        let mut out = self.duplicate();
+        for byte in out.pixels_mut().as_bytes_mut() {
+            *byte = !*byte;
+        }
+
+        out
+    }
+
+    // Mixes `alpha` of this image with `1.0 - alpha` of `other`. Where `other` is smaller
+    // this image is used as is.
+    pub fn blended(&self, other: &Bitmap, alpha: f32, dither_type: DitherType) -> Bitmap {
+        let (mut values, width, height) = self.greyscale();
+        let other = other.pixels();
+        let alpha = alpha.clamp(0.0, 1.0);
+
+        for y in 0..height {
+            for x in 0..width {
+                let value = &mut values[y * width + x];
+                if let Some(white) = other.white(x as i32, y as i32) {
+                    let other_value = if white { 255.0 } else { 0.0 };
+                    *value = (alpha * *value as f32 + (1.0 - alpha) * other_value) as i16;
+                }
+            }
+        }
+
+        let mut out = self.duplicate();
+        out.set_greyscale(&mut values, width, dither_type);
+        out
+    }
+
+    // Imitates pausing a video tape: a band of static, and rows torn sideways around it
+    pub fn vcr_pause_filter(&self) -> Bitmap {
+        let mut out = self.duplicate();
+        let mut pixels = out.pixels_mut();
+        let (width, height) = (pixels.width(), pixels.height());
+        if width == 0 || height == 0 {
+            return out;
+        }
+
+        let band_height = (height / 8).max(1);
+        let band_start = rng::rand().rem_euclid(height);
+
+        for y in band_start..(band_start + band_height).min(height) {
+            // Mostly white noise, getting denser towards the middle of the band
+            let distance = (y - band_start - band_height / 2).abs();
+            let density = 256 - distance * 256 / band_height;
+
+            for x in 0..width {
+                let white = rng::rand().rem_euclid(256) < density;
+                pixels.set_white(x, y, white);
+            }
+        }
+
+        let tear_start = (band_start - band_height).max(0);
+        let tear_end = (band_start + band_height * 2).min(height);
+        let original: Vec<bool> = (0..height * width)
+            .map(|i| pixels.as_pixels().white(i % width, i / width) == Some(true))
+            .collect();
+
+        for y in tear_start..tear_end {
+            if (band_start..band_start + band_height).contains(&y) {
+                continue;
+            }
+
+            let shift = rng::rand().rem_euclid(7) - 3;
+            for x in 0..width {
+                let source_x = (x - shift).clamp(0, width - 1);
+                let white = original[(y * width + source_x) as usize];
+                pixels.set_white(x, y, white);
+            }
+        }
+
+        out
+    }
+
+    fn greyscale(&self) -> (Vec<i16>, usize, usize) {
+        let pixels = self.pixels();
+        let (width, height) = (pixels.width() as usize, pixels.height() as usize);
+        let mut values = vec![0; width * height];
+
+        for (i, value) in values.iter_mut().enumerate() {
+            if pixels.white((i % width) as i32, (i / width) as i32) == Some(true) {
+                *value = 255;
+            }
+        }
+
+        (values, width, height)
+    }
+
+    fn set_greyscale(&mut self, values: &mut [i16], width: usize, dither_type: DitherType) {
+        let mut pixels = self.pixels_mut();
+        dither(values, width, dither_type, |x, y, white| {
+            pixels.set_white(x, y, white);
+        });
+    }
+}
+
+// Averages each value with its neighbours up to `radius` away, first across each row and
+// then down each column. Edge values are repeated past the edges.
+fn box_blur(values: &mut [i16], width: usize, height: usize, radius: usize) {
+    if radius == 0 || width == 0 {
+        return;
+    }
+
+    let mut scratch = vec![0; width.max(height)];
+    let count = (radius * 2 + 1) as i32;
+
+    for y in 0..height {
+        for (x, out) in scratch[..width].iter_mut().enumerate() {
+            let sum: i32 = (x as isize - radius as isize..=(x + radius) as isize)
+                .map(|i| values[y * width + i.clamp(0, width as isize - 1) as usize] as i32)
+                .sum();
+            *out = (sum / count) as i16;
+        }
+
+        values[y * width..(y + 1) * width].copy_from_slice(&scratch[..width]);
+    }
+
+    for x in 0..width {
+        for (y, out) in scratch[..height].iter_mut().enumerate() {
+            let sum: i32 = (y as isize - radius as isize..=(y + radius) as isize)
+                .map(|i| values[i.clamp(0, height as isize - 1) as usize * width + x] as i32)
+                .sum();
+            *out = (sum / count) as i16;
+        }
+
+        for (y, &value) in scratch[..height].iter().enumerate() {
+            values[y * width + x] = value;
+        }
+    }
+}
@@ -1,8 +1,17 @@
-use alloc::ffi::CString;
+use alloc::{ffi::CString, vec::Vec};
 
 #[derive(Debug)]
 pub struct Error {
     pub message: CString,
 }
 
+impl Error {
+    pub(crate) fn new(message: impl Into<Vec<u8>>) -> Self {
+        let mut message = message.into();
+        message.retain(|&b| b != 0);
+        let message = CString::new(message).unwrap();
+        Self { message }
+    }
+}
+
 pub type Result<T> = core::result::Result<T, Error>;
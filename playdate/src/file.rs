@@ -11,6 +11,7 @@ use playdate_sys::{
 };
 
 const FS_FAILURE: i32 = -1;
+const READ_CHUNK_SIZE: u32 = 4096;
 
 pub struct FileSystem {
     _unused: [u8; 0],
@@ -72,6 +73,22 @@ impl FileSystem {
         Ok(File(file_ptr))
     }
 
+    pub fn read_file(&self, path: &CStr) -> Result<Vec<u8>> {
+        let mut file = self.open(path, FileOptions::READ | FileOptions::READ_DATA)?;
+        let mut data = Vec::new();
+
+        loop {
+            let chunk = file.read(READ_CHUNK_SIZE)?;
+            if chunk.is_empty() {
+                break;
+            }
+
+            data.extend_from_slice(&chunk);
+        }
+
+        Ok(data)
+    }
+
     fn fs_result_from_int(&self, result: i32) -> Result<()> {
         if result != FS_FAILURE {
             Ok(())
@@ -101,6 +118,8 @@ impl File {
         let mut buf = Vec::with_capacity(len as usize);
         let result = invoke_unsafe!(file.read, self.0, buf.as_mut_ptr() as _, len);
         self.fs_result(result)?;
+        // The SDK returns how many bytes it wrote, which is less than `len` at the end of the file
+        unsafe { buf.set_len(result as usize) };
         Ok(buf)
     }
 
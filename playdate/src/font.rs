@@ -3,18 +3,23 @@ use crate::{
     error::{Error, Result},
     libc,
 };
-use alloc::borrow::ToOwned;
+use alloc::{borrow::ToOwned, vec, vec::Vec};
 use core::{
     ffi::CStr,
     marker::PhantomData,
-    ptr::{null, null_mut},
+    mem::{self, ManuallyDrop},
+    ptr::{self, null, null_mut},
 };
 use playdate_sys::{
     LCDBitmap, LCDFont, LCDFontGlyph, LCDFontPage, PDStringEncoding_k16BitLEEncoding,
     PDStringEncoding_kASCIIEncoding, PDStringEncoding_kUTF8Encoding,
 };
 
-pub struct Font(*mut LCDFont);
+pub struct Font {
+    ptr: *mut LCDFont,
+    // Fonts made from data keep pointing into it, so it has to live as long as the font
+    _data: Option<Vec<u32>>,
+}
 
 impl Font {
     pub fn new(path: &CStr) -> Result<Self> {
@@ -27,15 +32,55 @@ impl Font {
             Err(Error { message })?;
         }
 
-        Ok(Self(ptr))
+        Ok(Self { ptr, _data: None })
+    }
+
+    // Takes the contents of a .pft file, e.g. from `include_bytes!`
+    pub fn from_data(data: &'static [u8]) -> Result<Self> {
+        let (data, wide) = parse_header(data)?;
+        if data.as_ptr().align_offset(mem::align_of::<u32>()) != 0 {
+            return Self::from_words(data, wide);
+        }
+
+        // The SDK only reads from font data even though it takes a mutable pointer
+        let ptr = invoke_unsafe!(graphics.makeFontFromData, data.as_ptr() as _, wide as _);
+        Self::from_data_ptr(ptr, None)
+    }
+
+    pub fn from_vec(data: Vec<u8>) -> Result<Self> {
+        let (data, wide) = parse_header(&data)?;
+        Self::from_words(data, wide)
+    }
+
+    fn from_words(data: &[u8], wide: bool) -> Result<Self> {
+        // Byte buffers make no alignment guarantees, so copy into a word aligned buffer
+        let mut words = vec![0u32; data.len().div_ceil(4)];
+        unsafe {
+            ptr::copy_nonoverlapping(data.as_ptr(), words.as_mut_ptr() as *mut u8, data.len())
+        };
+
+        let ptr = invoke_unsafe!(
+            graphics.makeFontFromData,
+            words.as_mut_ptr() as _,
+            wide as _
+        );
+        Self::from_data_ptr(ptr, Some(words))
+    }
+
+    fn from_data_ptr(ptr: *mut LCDFont, data: Option<Vec<u32>>) -> Result<Self> {
+        if ptr.is_null() {
+            return Err(Error::new("invalid font data"));
+        }
+
+        Ok(Self { ptr, _data: data })
     }
 
     pub fn height(&self) -> u8 {
-        invoke_unsafe!(graphics.getFontHeight, self.0)
+        invoke_unsafe!(graphics.getFontHeight, self.ptr)
     }
 
     pub fn font_page<'a>(&'a self, c: u32) -> FontPage<'a> {
-        let ptr = invoke_unsafe!(graphics.getFontPage, self.0, c);
+        let ptr = invoke_unsafe!(graphics.getFontPage, self.ptr, c);
         FontPage {
             lifetime: Default::default(),
             ptr,
@@ -51,7 +96,7 @@ impl Font {
     ) -> i32 {
         invoke_unsafe!(
             graphics.getTextWidth,
-            self.0,
+            self.ptr,
             text.as_ptr() as _,
             len,
             encoding as _,
@@ -59,14 +104,63 @@ impl Font {
         )
     }
 
+    pub fn str_width(&self, text: &str, tracking: i32) -> i32 {
+        // Like drawText, the length is in characters and the text needn't be nul terminated
+        invoke_unsafe!(
+            graphics.getTextWidth,
+            self.ptr,
+            text.as_ptr() as _,
+            text.chars().count(),
+            TextEncoding::Utf8 as _,
+            tracking
+        )
+    }
+
+    // The advance of `c` plus its kerning against `next`, or 0 if the font has no such glyph
+    pub fn char_advance(&self, c: char, next: Option<char>) -> i32 {
+        let page = self.font_page(c as u32);
+        if page.ptr.is_null() {
+            return 0;
+        }
+
+        let glyph = page.glyph(c as u32);
+        if glyph.ptr.is_null() {
+            return 0;
+        }
+
+        let kerning = next.map_or(0, |next| glyph.kerning(c as u32, next as u32));
+        glyph.advance() + kerning
+    }
+
     pub(crate) fn as_mut_ptr(&self) -> *mut LCDFont {
-        self.0
+        self.ptr
     }
 }
 
+const MAGIC: &[u8] = b"Playdate FNT";
+const HEADER_SIZE: usize = 16;
+const FLAG_WIDE: u32 = 0x0000_0001;
+const FLAG_COMPRESSED: u32 = 0x8000_0000;
+
+// Splits a .pft file into the font data the SDK wants, which follows the 16 byte header, and
+// whether it has glyphs above U+1FFFF
+fn parse_header(data: &[u8]) -> Result<(&[u8], bool)> {
+    if data.len() < HEADER_SIZE || !data.starts_with(MAGIC) {
+        return Err(Error::new("invalid font data"));
+    }
+
+    let flags = u32::from_le_bytes([data[12], data[13], data[14], data[15]]);
+    if flags & FLAG_COMPRESSED > 0 {
+        return Err(Error::new("compressed fonts aren't supported"));
+    }
+
+    Ok((&data[HEADER_SIZE..], flags & FLAG_WIDE > 0))
+}
+
 impl Drop for Font {
     fn drop(&mut self) {
-        unsafe { libc::free(self.0 as _) };
+        // Fonts are allocated by the SDK, so they have to go back through its allocator
+        invoke_unsafe!(system.realloc, self.ptr as _, 0);
     }
 }
 
@@ -103,15 +197,16 @@ pub struct Glyph<'a> {
     lifetime: PhantomData<&'a ()>,
     ptr: *mut LCDFontGlyph,
     advance: i32,
-    bitmap: Bitmap,
+    bitmap: ManuallyDrop<Bitmap>,
 }
 
 impl<'a> Glyph<'a> {
     fn new(ptr: *mut LCDFontGlyph, advance: i32, bitmap: *mut LCDBitmap) -> Self {
-        let bitmap = Bitmap {
+        // Glyph bitmaps belong to the font, so they must never be freed here
+        let bitmap = ManuallyDrop::new(Bitmap {
             ptr: bitmap,
             mask: None,
-        };
+        });
 
         Self {
             lifetime: Default::default(),
@@ -133,3 +228,32 @@ impl<'a> Glyph<'a> {
         &self.bitmap
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(flags: u32) -> Vec<u8> {
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&flags.to_le_bytes());
+        data.extend_from_slice(&[1, 2, 3]);
+        data
+    }
+
+    #[test]
+    fn strips_header() {
+        let data = header(0);
+        assert_eq!(parse_header(&data).unwrap(), (&[1u8, 2, 3][..], false));
+
+        let data = header(FLAG_WIDE);
+        assert_eq!(parse_header(&data).unwrap(), (&[1u8, 2, 3][..], true));
+    }
+
+    #[test]
+    fn rejects_bad_headers() {
+        assert!(parse_header(&[]).is_err());
+        assert!(parse_header(&MAGIC[..8]).is_err());
+        assert!(parse_header(b"Playdate IMG\0\0\0\0").is_err());
+        assert!(parse_header(&header(FLAG_COMPRESSED)).is_err());
+    }
+}
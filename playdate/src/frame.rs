@@ -0,0 +1,182 @@
+use crate::gfx::{Graphics, SolidColor};
+use core::{marker::PhantomData, slice};
+use playdate_sys::{LCD_COLUMNS, LCD_ROWS, LCD_ROWSIZE};
+
+// Each row packs 8 pixels per byte, most significant bit first, with set bits being white.
+// Rows are padded out to `STRIDE` bytes.
+const WIDTH: i32 = LCD_COLUMNS as i32;
+const HEIGHT: i32 = LCD_ROWS as i32;
+const STRIDE: usize = LCD_ROWSIZE as usize;
+
+fn pixel(data: &[u8], x: i32, y: i32) -> Option<SolidColor> {
+    if !(0..WIDTH).contains(&x) || !(0..HEIGHT).contains(&y) {
+        return None;
+    }
+
+    let byte = data[y as usize * STRIDE + x as usize / 8];
+    let white = byte & (0x80 >> (x % 8)) != 0;
+    Some(if white {
+        SolidColor::White
+    } else {
+        SolidColor::Black
+    })
+}
+
+fn row(data: &[u8], y: i32) -> Option<&[u8]> {
+    if !(0..HEIGHT).contains(&y) {
+        return None;
+    }
+
+    let start = y as usize * STRIDE;
+    Some(&data[start..start + STRIDE])
+}
+
+// Applies `color` to the bits of `byte` selected by `mask`
+fn apply(byte: &mut u8, mask: u8, color: SolidColor) {
+    match color {
+        SolidColor::Black => *byte &= !mask,
+        SolidColor::White => *byte |= mask,
+        SolidColor::Xor => *byte ^= mask,
+        SolidColor::Clear => {}
+    }
+}
+
+// Direct access to the frame being drawn. Rows written through the guard are passed to
+// `mark_updated_rows` when it drops, so they're sent to the display on the next update.
+pub struct FrameBuffer<'a> {
+    data: &'a mut [u8],
+    dirty: Option<(i32, i32)>,
+    _gfx: PhantomData<&'a mut Graphics>,
+}
+
+impl<'a> FrameBuffer<'a> {
+    pub const WIDTH: i32 = WIDTH;
+    pub const HEIGHT: i32 = HEIGHT;
+    pub const STRIDE: usize = STRIDE;
+
+    pub(crate) fn new(_gfx: &'a mut Graphics) -> Self {
+        let ptr = invoke_unsafe!(graphics.getFrame);
+        let data = unsafe { slice::from_raw_parts_mut(ptr, STRIDE * HEIGHT as usize) };
+
+        Self {
+            data,
+            dirty: None,
+            _gfx: PhantomData,
+        }
+    }
+
+    pub fn pixel(&self, x: i32, y: i32) -> Option<SolidColor> {
+        pixel(self.data, x, y)
+    }
+
+    // Pixels outside of the screen are ignored, and `Clear` leaves the pixel as it was
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: SolidColor) {
+        if !(0..WIDTH).contains(&x) {
+            return;
+        }
+
+        if let Some(row) = self.row_mut(y) {
+            apply(&mut row[x as usize / 8], 0x80 >> (x % 8), color);
+        }
+    }
+
+    // Sets the pixels from `start_x` up to but not including `end_x` on row `y`
+    pub fn fill_span(&mut self, y: i32, start_x: i32, end_x: i32, color: SolidColor) {
+        let start_x = start_x.max(0);
+        let end_x = end_x.min(WIDTH);
+        if start_x >= end_x {
+            return;
+        }
+
+        let Some(row) = self.row_mut(y) else {
+            return;
+        };
+        let first = start_x as usize / 8;
+        let last = (end_x as usize - 1) / 8;
+        let first_mask = 0xff >> (start_x % 8);
+        let last_mask = 0xff << (7 - (end_x - 1) % 8);
+
+        if first == last {
+            apply(&mut row[first], first_mask & last_mask, color);
+            return;
+        }
+
+        apply(&mut row[first], first_mask, color);
+        for byte in &mut row[first + 1..last] {
+            apply(byte, 0xff, color);
+        }
+        apply(&mut row[last], last_mask, color);
+    }
+
+    pub fn row(&self, y: i32) -> Option<&[u8]> {
+        row(self.data, y)
+    }
+
+    pub fn row_mut(&mut self, y: i32) -> Option<&mut [u8]> {
+        if !(0..HEIGHT).contains(&y) {
+            return None;
+        }
+
+        self.mark_row(y);
+        let start = y as usize * STRIDE;
+        Some(&mut self.data[start..start + STRIDE])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        self.data.chunks_exact(STRIDE)
+    }
+
+    // For writes that don't go through the guard's own methods. Rows outside of the screen
+    // are ignored.
+    pub fn mark_row(&mut self, y: i32) {
+        if !(0..HEIGHT).contains(&y) {
+            return;
+        }
+
+        self.dirty = Some(match self.dirty {
+            Some((start, end)) => (start.min(y), end.max(y)),
+            None => (y, y),
+        });
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data
+    }
+}
+
+impl Drop for FrameBuffer<'_> {
+    fn drop(&mut self) {
+        if let Some((start, end)) = self.dirty {
+            invoke_unsafe!(graphics.markUpdatedRows, start, end);
+        }
+    }
+}
+
+// The frame that's currently on the display, which is read only
+pub struct DisplayFrame<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> DisplayFrame<'a> {
+    pub(crate) fn new(_gfx: &'a Graphics) -> Self {
+        let ptr = invoke_unsafe!(graphics.getDisplayFrame);
+        let data = unsafe { slice::from_raw_parts(ptr, STRIDE * HEIGHT as usize) };
+        Self { data }
+    }
+
+    pub fn pixel(&self, x: i32, y: i32) -> Option<SolidColor> {
+        pixel(self.data, x, y)
+    }
+
+    pub fn row(&self, y: i32) -> Option<&[u8]> {
+        row(self.data, y)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        self.data.chunks_exact(STRIDE)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data
+    }
+}
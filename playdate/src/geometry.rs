@@ -0,0 +1,556 @@
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use playdate_sys::{CollisionPoint, CollisionVector, LCDRect, PDRect};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IntPoint {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LineSegment {
+    pub start: Point,
+    pub end: Point,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Size {
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IntSize {
+    pub width: i32,
+    pub height: i32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IntRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+macro_rules! point {
+    ( $point:ident, $size:ident, $scalar:ty, $zero:expr ) => {
+        impl $point {
+            pub const ZERO: Self = Self::new($zero, $zero);
+
+            pub const fn new(x: $scalar, y: $scalar) -> Self {
+                Self { x, y }
+            }
+        }
+
+        impl Add for $point {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self::new(self.x + rhs.x, self.y + rhs.y)
+            }
+        }
+
+        impl Sub for $point {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self::new(self.x - rhs.x, self.y - rhs.y)
+            }
+        }
+
+        impl AddAssign for $point {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl SubAssign for $point {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl Mul<$scalar> for $point {
+            type Output = Self;
+
+            fn mul(self, rhs: $scalar) -> Self::Output {
+                Self::new(self.x * rhs, self.y * rhs)
+            }
+        }
+
+        impl Div<$scalar> for $point {
+            type Output = Self;
+
+            fn div(self, rhs: $scalar) -> Self::Output {
+                Self::new(self.x / rhs, self.y / rhs)
+            }
+        }
+
+        impl Neg for $point {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self::new(-self.x, -self.y)
+            }
+        }
+
+        impl $size {
+            pub const ZERO: Self = Self::new($zero, $zero);
+
+            pub const fn new(width: $scalar, height: $scalar) -> Self {
+                Self { width, height }
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.width <= $zero || self.height <= $zero
+            }
+        }
+
+        impl Mul<$scalar> for $size {
+            type Output = Self;
+
+            fn mul(self, rhs: $scalar) -> Self::Output {
+                Self::new(self.width * rhs, self.height * rhs)
+            }
+        }
+
+        impl Div<$scalar> for $size {
+            type Output = Self;
+
+            fn div(self, rhs: $scalar) -> Self::Output {
+                Self::new(self.width / rhs, self.height / rhs)
+            }
+        }
+    };
+}
+
+point!(Point, Size, f32, 0.0);
+point!(IntPoint, IntSize, i32, 0);
+
+// Rects span [x, x + width) horizontally and [y, y + height) vertically, so rects that
+// only share an edge don't intersect
+macro_rules! rect {
+    ( $rect:ident, $point:ident, $size:ident, $scalar:ty, $zero:expr, $two:expr ) => {
+        impl $rect {
+            pub const fn new(x: $scalar, y: $scalar, width: $scalar, height: $scalar) -> Self {
+                Self {
+                    x,
+                    y,
+                    width,
+                    height,
+                }
+            }
+
+            pub fn from_origin_size(origin: $point, size: $size) -> Self {
+                Self::new(origin.x, origin.y, size.width, size.height)
+            }
+
+            // Builds the rect covering both points, whichever corners they are
+            pub fn from_points(a: $point, b: $point) -> Self {
+                let x = if a.x < b.x { a.x } else { b.x };
+                let y = if a.y < b.y { a.y } else { b.y };
+                let max_x = if a.x > b.x { a.x } else { b.x };
+                let max_y = if a.y > b.y { a.y } else { b.y };
+                Self::new(x, y, max_x - x, max_y - y)
+            }
+
+            pub fn origin(&self) -> $point {
+                $point::new(self.x, self.y)
+            }
+
+            pub fn size(&self) -> $size {
+                $size::new(self.width, self.height)
+            }
+
+            pub fn min_x(&self) -> $scalar {
+                self.x
+            }
+
+            pub fn min_y(&self) -> $scalar {
+                self.y
+            }
+
+            pub fn max_x(&self) -> $scalar {
+                self.x + self.width
+            }
+
+            pub fn max_y(&self) -> $scalar {
+                self.y + self.height
+            }
+
+            pub fn center(&self) -> $point {
+                $point::new(self.x + self.width / $two, self.y + self.height / $two)
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.size().is_empty()
+            }
+
+            pub fn contains(&self, point: $point) -> bool {
+                point.x >= self.min_x()
+                    && point.x < self.max_x()
+                    && point.y >= self.min_y()
+                    && point.y < self.max_y()
+            }
+
+            // Like `contains`, but also counts points on the right and bottom edges
+            pub fn contains_inclusive(&self, point: $point) -> bool {
+                point.x >= self.min_x()
+                    && point.x <= self.max_x()
+                    && point.y >= self.min_y()
+                    && point.y <= self.max_y()
+            }
+
+            pub fn contains_rect(&self, other: &Self) -> bool {
+                other.min_x() >= self.min_x()
+                    && other.max_x() <= self.max_x()
+                    && other.min_y() >= self.min_y()
+                    && other.max_y() <= self.max_y()
+            }
+
+            pub fn intersects(&self, other: &Self) -> bool {
+                !self.is_empty()
+                    && !other.is_empty()
+                    && self.min_x() < other.max_x()
+                    && other.min_x() < self.max_x()
+                    && self.min_y() < other.max_y()
+                    && other.min_y() < self.max_y()
+            }
+
+            // Like `intersects`, but also true for rects that only share an edge and for empty
+            // rects inside or on the edge of the other
+            pub fn intersects_inclusive(&self, other: &Self) -> bool {
+                self.min_x() <= other.max_x()
+                    && other.min_x() <= self.max_x()
+                    && self.min_y() <= other.max_y()
+                    && other.min_y() <= self.max_y()
+            }
+
+            pub fn intersection(&self, other: &Self) -> Option<Self> {
+                if !self.intersects(other) {
+                    return None;
+                }
+
+                let min = $point::new(
+                    if self.x > other.x { self.x } else { other.x },
+                    if self.y > other.y { self.y } else { other.y },
+                );
+                let max = $point::new(
+                    if self.max_x() < other.max_x() {
+                        self.max_x()
+                    } else {
+                        other.max_x()
+                    },
+                    if self.max_y() < other.max_y() {
+                        self.max_y()
+                    } else {
+                        other.max_y()
+                    },
+                );
+
+                Some(Self::from_points(min, max))
+            }
+
+            // The smallest rect containing both; empty rects are ignored
+            pub fn union(&self, other: &Self) -> Self {
+                if self.is_empty() {
+                    return *other;
+                }
+
+                if other.is_empty() {
+                    return *self;
+                }
+
+                let min = $point::new(
+                    if self.x < other.x { self.x } else { other.x },
+                    if self.y < other.y { self.y } else { other.y },
+                );
+                let max = $point::new(
+                    if self.max_x() > other.max_x() {
+                        self.max_x()
+                    } else {
+                        other.max_x()
+                    },
+                    if self.max_y() > other.max_y() {
+                        self.max_y()
+                    } else {
+                        other.max_y()
+                    },
+                );
+
+                Self::from_points(min, max)
+            }
+
+            // Shrinks the rect by `dx` on the left and right and `dy` on the top and
+            // bottom. Negative values grow it instead.
+            pub fn inset(&self, dx: $scalar, dy: $scalar) -> Self {
+                let width = self.width - dx * $two;
+                let height = self.height - dy * $two;
+                Self::new(
+                    self.x + dx,
+                    self.y + dy,
+                    if width > $zero { width } else { $zero },
+                    if height > $zero { height } else { $zero },
+                )
+            }
+
+            pub fn offset(&self, dx: $scalar, dy: $scalar) -> Self {
+                Self::new(self.x + dx, self.y + dy, self.width, self.height)
+            }
+        }
+
+        impl Add<$point> for $rect {
+            type Output = Self;
+
+            fn add(self, rhs: $point) -> Self::Output {
+                self.offset(rhs.x, rhs.y)
+            }
+        }
+
+        impl Sub<$point> for $rect {
+            type Output = Self;
+
+            fn sub(self, rhs: $point) -> Self::Output {
+                self.offset(-rhs.x, -rhs.y)
+            }
+        }
+
+        impl Mul<$scalar> for $rect {
+            type Output = Self;
+
+            fn mul(self, rhs: $scalar) -> Self::Output {
+                Self::new(
+                    self.x * rhs,
+                    self.y * rhs,
+                    self.width * rhs,
+                    self.height * rhs,
+                )
+            }
+        }
+    };
+}
+
+rect!(Rect, Point, Size, f32, 0.0, 2.0);
+rect!(IntRect, IntPoint, IntSize, i32, 0, 2);
+
+impl Point {
+    pub fn length(&self) -> f32 {
+        crate::math::sqrt(self.x * self.x + self.y * self.y)
+    }
+
+    pub fn distance(&self, other: Point) -> f32 {
+        (*self - other).length()
+    }
+
+    pub fn round(&self) -> IntPoint {
+        IntPoint::new(
+            crate::math::round(self.x) as i32,
+            crate::math::round(self.y) as i32,
+        )
+    }
+}
+
+impl LineSegment {
+    pub const fn new(start: Point, end: Point) -> Self {
+        Self { start, end }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.start.distance(self.end)
+    }
+
+    pub fn midpoint(&self) -> Point {
+        (self.start + self.end) / 2.0
+    }
+}
+
+impl Rect {
+    // Rounds outwards so that the int rect covers every pixel the rect touches
+    pub fn round_out(&self) -> IntRect {
+        let min = IntPoint::new(floor(self.min_x()), floor(self.min_y()));
+        let max = IntPoint::new(ceil(self.max_x()), ceil(self.max_y()));
+        IntRect::from_points(min, max)
+    }
+}
+
+fn floor(value: f32) -> i32 {
+    let truncated = value as i32;
+    if (truncated as f32) > value {
+        truncated - 1
+    } else {
+        truncated
+    }
+}
+
+fn ceil(value: f32) -> i32 {
+    let truncated = value as i32;
+    if (truncated as f32) < value {
+        truncated + 1
+    } else {
+        truncated
+    }
+}
+
+impl From<IntPoint> for Point {
+    fn from(point: IntPoint) -> Self {
+        Self::new(point.x as f32, point.y as f32)
+    }
+}
+
+impl From<IntSize> for Size {
+    fn from(size: IntSize) -> Self {
+        Self::new(size.width as f32, size.height as f32)
+    }
+}
+
+impl From<IntRect> for Rect {
+    fn from(rect: IntRect) -> Self {
+        Self::new(
+            rect.x as f32,
+            rect.y as f32,
+            rect.width as f32,
+            rect.height as f32,
+        )
+    }
+}
+
+impl From<CollisionPoint> for Point {
+    fn from(point: CollisionPoint) -> Self {
+        Self::new(point.x, point.y)
+    }
+}
+
+impl From<Point> for CollisionPoint {
+    fn from(point: Point) -> Self {
+        Self {
+            x: point.x,
+            y: point.y,
+        }
+    }
+}
+
+impl From<CollisionVector> for IntPoint {
+    fn from(vector: CollisionVector) -> Self {
+        Self::new(vector.x, vector.y)
+    }
+}
+
+impl From<IntPoint> for CollisionVector {
+    fn from(point: IntPoint) -> Self {
+        Self {
+            x: point.x,
+            y: point.y,
+        }
+    }
+}
+
+impl From<PDRect> for Rect {
+    fn from(rect: PDRect) -> Self {
+        Self::new(rect.x, rect.y, rect.width, rect.height)
+    }
+}
+
+impl From<Rect> for PDRect {
+    fn from(rect: Rect) -> Self {
+        Self {
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+        }
+    }
+}
+
+// LCDRect stores edges rather than a size, with right and bottom exclusive
+impl From<LCDRect> for IntRect {
+    fn from(rect: LCDRect) -> Self {
+        Self::new(
+            rect.left,
+            rect.top,
+            rect.right - rect.left,
+            rect.bottom - rect.top,
+        )
+    }
+}
+
+impl From<IntRect> for LCDRect {
+    fn from(rect: IntRect) -> Self {
+        Self {
+            left: rect.min_x(),
+            right: rect.max_x(),
+            top: rect.min_y(),
+            bottom: rect.max_y(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_is_half_open() {
+        let rect = IntRect::new(0, 0, 10, 10);
+        assert!(rect.contains(IntPoint::new(0, 0)));
+        assert!(rect.contains(IntPoint::new(9, 9)));
+        assert!(!rect.contains(IntPoint::new(10, 5)));
+        assert!(rect.contains_inclusive(IntPoint::new(10, 10)));
+        assert!(!rect.contains_inclusive(IntPoint::new(11, 10)));
+    }
+
+    #[test]
+    fn touching_rects() {
+        let a = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let b = Rect::new(10.0, 0.0, 5.0, 5.0);
+        assert!(!a.intersects(&b));
+        assert!(a.intersects_inclusive(&b));
+        assert_eq!(a.intersection(&b), None);
+
+        let empty = Rect::new(5.0, 5.0, 0.0, 0.0);
+        assert!(!a.intersects(&empty));
+        assert!(a.intersects_inclusive(&empty));
+        assert!(!a.intersects_inclusive(&Rect::new(11.0, 0.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn intersection_and_union() {
+        let a = IntRect::new(0, 0, 10, 10);
+        let b = IntRect::new(5, -5, 10, 10);
+        assert_eq!(a.intersection(&b), Some(IntRect::new(5, 0, 5, 5)));
+        assert_eq!(a.union(&b), IntRect::new(0, -5, 15, 15));
+        assert_eq!(a.union(&IntRect::default()), a);
+        assert!(a.contains_rect(&IntRect::new(2, 2, 8, 8)));
+        assert!(!a.contains_rect(&b));
+    }
+
+    #[test]
+    fn from_points_and_inset() {
+        let rect = IntRect::from_points(IntPoint::new(4, 1), IntPoint::new(0, 3));
+        assert_eq!(rect, IntRect::new(0, 1, 4, 2));
+        assert_eq!(rect.inset(1, 1), IntRect::new(1, 2, 2, 0));
+        assert_eq!(rect.inset(-1, 0), IntRect::new(-1, 1, 6, 2));
+        assert_eq!(rect + IntPoint::new(1, 1), IntRect::new(1, 2, 4, 2));
+    }
+
+    #[test]
+    fn round_out() {
+        let rect = Rect::new(-0.5, 0.25, 1.0, 1.5);
+        assert_eq!(rect.round_out(), IntRect::new(-1, 0, 2, 2));
+        assert_eq!(Point::new(3.0, 4.0).length(), 5.0);
+    }
+}
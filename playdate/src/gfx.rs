@@ -1,8 +1,14 @@
 use crate::{
     bitmap::{Bitmap, BitmapFlip},
+    context::{draw_state, ClipRect, ContextGuard, DrawState, ScopedState},
     error::Result,
     font::{Font, TextEncoding},
+    frame::{DisplayFrame, FrameBuffer},
+    geometry::{IntPoint, IntRect, LineSegment, Point},
+    image::ImageDecoder,
+    pattern::Pattern,
     sprite::{DrawMode, TileMode},
+    transform::AffineTransform,
 };
 use alloc::vec::Vec;
 use core::{
@@ -11,16 +17,17 @@ use core::{
     ptr::null_mut,
 };
 use playdate_sys::{
-    LCDLineCapStyle_kLineCapStyleButt, LCDLineCapStyle_kLineCapStyleRound,
+    LCDColor, LCDLineCapStyle_kLineCapStyleButt, LCDLineCapStyle_kLineCapStyleRound,
     LCDLineCapStyle_kLineCapStyleSquare, LCDPolygonFillRule_kPolygonFillEvenOdd,
     LCDPolygonFillRule_kPolygonFillNonZero, LCDSolidColor_kColorBlack, LCDSolidColor_kColorClear,
     LCDSolidColor_kColorWhite, LCDSolidColor_kColorXOR,
 };
 
 pub struct Graphics {
-    font: Option<Font>,
-    stencil: Option<Bitmap>,
-    context_stack: Vec<Option<Bitmap>>,
+    pub(crate) font: Option<Font>,
+    pub(crate) stencil: Option<(Bitmap, TileMode)>,
+    context_stack: Vec<(Option<Bitmap>, DrawState)>,
+    pub(crate) scopes: Vec<ScopedState>,
 }
 
 impl Graphics {
@@ -29,6 +36,7 @@ impl Graphics {
             font: Default::default(),
             stencil: Default::default(),
             context_stack: Default::default(),
+            scopes: Default::default(),
         }
     }
 
@@ -40,6 +48,12 @@ impl Graphics {
         Bitmap::load(path)
     }
 
+    // Decodes PNG, BMP or PBM data with the default dithering. Use `ImageDecoder` for
+    // more control over the conversion.
+    pub fn decode_bitmap(&self, data: &[u8]) -> Result<Bitmap> {
+        ImageDecoder::new().decode(data)
+    }
+
     pub fn rotated_bitmap(
         &self,
         bitmap: &Bitmap,
@@ -55,13 +69,33 @@ impl Graphics {
             .as_ref()
             .map(|bmp| bmp.as_mut_ptr())
             .unwrap_or(null_mut());
-        self.context_stack.push(context);
+        self.context_stack.push((context, *draw_state()));
         invoke_unsafe!(graphics.pushContext, bitmap_ptr);
     }
 
     pub fn pop_context(&mut self) -> Option<Bitmap> {
         invoke_unsafe!(graphics.popContext);
-        self.context_stack.pop().and_then(|ctx| ctx)
+        let (context, state) = self.context_stack.pop()?;
+        *draw_state() = state;
+        context
+    }
+
+    pub fn with_context<F, R>(&mut self, target: &mut Bitmap, f: F) -> R
+    where
+        F: FnOnce(&mut Graphics) -> R,
+    {
+        let mut guard = self.context(target);
+        f(&mut guard)
+    }
+
+    // Draws into `target` until the guard drops
+    pub fn context<'a>(&'a mut self, target: &'a mut Bitmap) -> ContextGuard<'a> {
+        ContextGuard::new(self, Some(target))
+    }
+
+    // Keeps drawing to the current target, but restores the drawing state when the guard drops
+    pub fn scope(&mut self) -> ContextGuard<'_> {
+        ContextGuard::new(self, None)
     }
 
     pub fn set_stencil(&mut self, stencil: Option<Bitmap>) -> Option<Bitmap> {
@@ -69,9 +103,9 @@ impl Graphics {
             .as_ref()
             .map(|s| s.as_mut_ptr())
             .unwrap_or(null_mut());
-        let last_stencil = mem::replace(&mut self.stencil, stencil);
+        let stencil = stencil.map(|stencil| (stencil, TileMode::NoTile));
         invoke_unsafe!(graphics.setStencil, stencil_ptr);
-        last_stencil
+        self.replace_stencil(stencil)
     }
 
     pub fn set_stencil_image(
@@ -83,43 +117,94 @@ impl Graphics {
             .as_ref()
             .map(|s| s.as_mut_ptr())
             .unwrap_or(null_mut());
-        let last_stencil = mem::replace(&mut self.stencil, stencil);
+        let stencil = stencil.map(|stencil| (stencil, tile_mode));
         invoke_unsafe!(graphics.setStencilImage, stencil_ptr, tile_mode as _);
-        last_stencil
+        self.replace_stencil(stencil)
+    }
+
+    fn replace_stencil(&mut self, stencil: Option<(Bitmap, TileMode)>) -> Option<Bitmap> {
+        draw_state().stencil = stencil
+            .as_ref()
+            .map_or((null_mut(), TileMode::NoTile), |(s, tile)| {
+                (s.as_mut_ptr(), *tile)
+            });
+        let last_stencil = mem::replace(&mut self.stencil, stencil);
+
+        match self.scopes.last_mut() {
+            // The open guard puts this stencil back when it drops
+            Some(scope) if scope.stencil.is_none() => {
+                scope.stencil = Some(last_stencil);
+                None
+            }
+            _ => last_stencil.map(|(stencil, _)| stencil),
+        }
     }
 
     pub fn set_draw_mode(&mut self, draw_mode: DrawMode) {
+        draw_state().draw_mode = draw_mode;
         invoke_unsafe!(graphics.setDrawMode, draw_mode as _);
     }
 
-    pub fn set_clip_rect(&mut self, x: i32, y: i32, width: i32, height: i32) {
-        invoke_unsafe!(graphics.setClipRect, x, y, width, height)
+    pub fn set_clip_rect(&mut self, rect: IntRect) {
+        draw_state().clip_rect = ClipRect::World(rect);
+        invoke_unsafe!(
+            graphics.setClipRect,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height
+        )
     }
 
-    pub fn set_screen_clip_rect(&mut self, x: i32, y: i32, width: i32, height: i32) {
-        invoke_unsafe!(graphics.setScreenClipRect, x, y, width, height)
+    pub fn set_screen_clip_rect(&mut self, rect: IntRect) {
+        draw_state().clip_rect = ClipRect::Screen(rect);
+        invoke_unsafe!(
+            graphics.setScreenClipRect,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height
+        )
     }
 
     pub fn clear_clip_rect(&mut self) {
+        draw_state().clip_rect = ClipRect::None;
         invoke_unsafe!(graphics.clearClipRect)
     }
 
     pub fn set_line_cap_style(&mut self, style: LineCapStyle) {
+        draw_state().line_cap_style = style;
         invoke_unsafe!(graphics.setLineCapStyle, style as _)
     }
 
     pub fn set_font(&mut self, font: Font) -> Option<Font> {
-        let font_ptr = font.as_mut_ptr();
+        self.use_font(&font);
         let last_font = mem::replace(&mut self.font, Some(font));
-        invoke_unsafe!(graphics.setFont, font_ptr);
-        last_font
+
+        match self.scopes.last_mut() {
+            // The open guard puts this font back when it drops
+            Some(scope) if scope.font.is_none() => {
+                scope.font = Some(last_font);
+                None
+            }
+            _ => last_font,
+        }
+    }
+
+    // Draws with `font` without taking ownership of it, so it must only be used inside a
+    // `scope()` that ends before the font is dropped
+    pub(crate) fn use_font(&mut self, font: &Font) {
+        draw_state().font = font.as_mut_ptr();
+        invoke_unsafe!(graphics.setFont, font.as_mut_ptr());
     }
 
     pub fn set_text_tracking(&mut self, tracking: i32) {
+        draw_state().tracking = tracking;
         invoke_unsafe!(graphics.setTextTracking, tracking)
     }
 
     pub fn set_text_leading(&mut self, leading: i32) {
+        draw_state().leading = leading;
         invoke_unsafe!(graphics.setTextLeading, leading)
     }
 
@@ -155,12 +240,23 @@ impl Graphics {
         );
     }
 
+    pub fn draw_str(&mut self, text: &str, x: i32, y: i32) {
+        // drawText takes the length in characters rather than bytes, and stops at the given
+        // length so the string does not need to be nul terminated
+        let len = text.chars().count();
+        invoke_unsafe!(
+            graphics.drawText,
+            text.as_ptr() as _,
+            len,
+            TextEncoding::Utf8 as _,
+            x,
+            y
+        );
+    }
+
     pub fn draw_ellipse(
         &mut self,
-        x: i32,
-        y: i32,
-        width: i32,
-        height: i32,
+        rect: IntRect,
         line_width: i32,
         start_angle: f32,
         end_angle: f32,
@@ -168,85 +264,147 @@ impl Graphics {
     ) {
         invoke_unsafe!(
             graphics.drawEllipse,
-            x,
-            y,
-            width,
-            height,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
             line_width,
             start_angle,
             end_angle,
-            color as _
+            color.as_lcd_color()
         )
     }
 
-    pub fn fill_ellipse(
-        &mut self,
-        x: i32,
-        y: i32,
-        width: i32,
-        height: i32,
-        start_angle: f32,
-        end_angle: f32,
-        color: Color,
-    ) {
+    pub fn fill_ellipse(&mut self, rect: IntRect, start_angle: f32, end_angle: f32, color: Color) {
         invoke_unsafe!(
             graphics.fillEllipse,
-            x,
-            y,
-            width,
-            height,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
             start_angle,
             end_angle,
-            color as _
+            color.as_lcd_color()
+        )
+    }
+
+    pub fn draw_line(&mut self, start: IntPoint, end: IntPoint, width: i32, color: Color) {
+        invoke_unsafe!(
+            graphics.drawLine,
+            start.x,
+            start.y,
+            end.x,
+            end.y,
+            width,
+            color.as_lcd_color()
+        )
+    }
+
+    pub fn draw_rect(&mut self, rect: IntRect, color: Color) {
+        invoke_unsafe!(
+            graphics.drawRect,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            color.as_lcd_color()
         )
     }
 
-    pub fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, width: i32, color: Color) {
-        invoke_unsafe!(graphics.drawLine, x1, y1, x2, y2, width, color as _)
+    pub fn fill_rect(&mut self, rect: IntRect, color: Color) {
+        invoke_unsafe!(
+            graphics.fillRect,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            color.as_lcd_color()
+        )
     }
 
-    pub fn draw_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color) {
-        invoke_unsafe!(graphics.drawRect, x, y, width, height, color as _)
+    pub fn fill_triangle(&mut self, p1: IntPoint, p2: IntPoint, p3: IntPoint, color: Color) {
+        invoke_unsafe!(
+            graphics.fillTriangle,
+            p1.x,
+            p1.y,
+            p2.x,
+            p2.y,
+            p3.x,
+            p3.y,
+            color.as_lcd_color()
+        )
     }
 
-    pub fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color) {
-        invoke_unsafe!(graphics.fillRect, x, y, width, height, color as _)
+    pub fn fill_polygon(&mut self, points: &[IntPoint], color: Color, fill_rule: FillRule) {
+        let coords: Vec<_> = points.iter().flat_map(|point| [point.x, point.y]).collect();
+
+        invoke_unsafe!(
+            graphics.fillPolygon,
+            points.len() as _,
+            coords.as_ptr() as _,
+            color.as_lcd_color(),
+            fill_rule as _
+        )
+    }
+
+    // Draws a line through each point in turn without closing the shape
+    pub fn draw_polyline(&mut self, points: &[IntPoint], width: i32, color: Color) {
+        for pair in points.windows(2) {
+            self.draw_line(pair[0], pair[1], width, color);
+        }
+    }
+
+    pub fn draw_line_transformed(
+        &mut self,
+        line: LineSegment,
+        transform: &AffineTransform,
+        width: i32,
+        color: Color,
+    ) {
+        let line = transform.transform_line(line);
+        self.draw_line(line.start.round(), line.end.round(), width, color)
     }
 
-    pub fn fill_triangle(
+    // Outlines the polygon, closing it back to the first point
+    pub fn draw_polygon_transformed(
         &mut self,
-        x1: i32,
-        y1: i32,
-        x2: i32,
-        y2: i32,
-        x3: i32,
-        y3: i32,
+        points: &[Point],
+        transform: &AffineTransform,
+        width: i32,
         color: Color,
     ) {
-        invoke_unsafe!(graphics.fillTriangle, x1, y1, x2, y2, x3, y3, color as _)
+        let points: Vec<_> = points
+            .iter()
+            .map(|&point| transform.transform_point(point).round())
+            .collect();
+
+        self.draw_polyline(&points, width, color);
+
+        if let [first, _, .., last] = points[..] {
+            self.draw_line(last, first, width, color);
+        }
     }
 
-    pub fn fill_polygon(
+    pub fn fill_polygon_transformed(
         &mut self,
-        num_points: i32,
-        points: &[i32],
+        points: &[Point],
+        transform: &AffineTransform,
         color: Color,
         fill_rule: FillRule,
     ) {
-        invoke_unsafe!(
-            graphics.fillPolygon,
-            num_points,
-            points.as_ptr() as _,
-            color as _,
-            fill_rule as _
-        )
+        let points: Vec<_> = points
+            .iter()
+            .map(|&point| transform.transform_point(point).round())
+            .collect();
+
+        self.fill_polygon(&points, color, fill_rule)
     }
 
     pub fn clear(&mut self, color: Color) {
-        invoke_unsafe!(graphics.clear, color as _)
+        invoke_unsafe!(graphics.clear, color.as_lcd_color())
     }
 
-    pub fn set_background_color(&mut self, color: Color) {
+    pub fn set_background_color(&mut self, color: SolidColor) {
         invoke_unsafe!(graphics.setBackgroundColor, color as _)
     }
 
@@ -271,8 +429,8 @@ impl Graphics {
         true
     }
 
-    pub fn display_frame(&mut self) -> *mut u8 {
-        invoke_unsafe!(graphics.getDisplayFrame)
+    pub fn display_frame(&self) -> DisplayFrame<'_> {
+        DisplayFrame::new(self)
     }
 
     pub fn with_display_buffer_bitmap<F>(&mut self, mut f: F)
@@ -286,8 +444,8 @@ impl Graphics {
         f(&mut bmp);
     }
 
-    pub fn frame(&mut self) -> *mut u8 {
-        invoke_unsafe!(graphics.getFrame)
+    pub fn frame(&mut self) -> FrameBuffer<'_> {
+        FrameBuffer::new(self)
     }
 
     pub fn copy_frame_buffer_bitmap(&self) -> Bitmap {
@@ -296,11 +454,17 @@ impl Graphics {
         Bitmap { ptr, mask }
     }
 
+    // Saves the current frame as a PNG in the game's data directory
+    pub fn screenshot(&self, path: &CStr) -> Result<()> {
+        self.copy_frame_buffer_bitmap().save_png(path)
+    }
+
     pub fn mark_updated_rows(&mut self, start: i32, end: i32) {
         invoke_unsafe!(graphics.markUpdatedRows, start, end)
     }
 
     pub fn set_draw_offset(&mut self, dx: i32, dy: i32) {
+        draw_state().draw_offset = (dx, dy);
         invoke_unsafe!(graphics.setDrawOffset, dx, dy)
     }
 }
@@ -315,15 +479,51 @@ pub enum LineCapStyle {
 
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum Color {
+pub enum SolidColor {
     Black = LCDSolidColor_kColorBlack,
     White = LCDSolidColor_kColorWhite,
     Clear = LCDSolidColor_kColorClear,
     Xor = LCDSolidColor_kColorXOR,
 }
 
-pub type Rect = playdate_sys::PDRect;
-pub type IntRect = playdate_sys::LCDRect;
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Color {
+    Black,
+    White,
+    Clear,
+    Xor,
+    Pattern(Pattern),
+}
+
+impl Color {
+    // Patterns are passed by pointer, so the result is only valid while `self` is alive
+    pub(crate) fn as_lcd_color(&self) -> LCDColor {
+        match self {
+            Self::Black => LCDSolidColor_kColorBlack as _,
+            Self::White => LCDSolidColor_kColorWhite as _,
+            Self::Clear => LCDSolidColor_kColorClear as _,
+            Self::Xor => LCDSolidColor_kColorXOR as _,
+            Self::Pattern(pattern) => pattern.as_lcd_color(),
+        }
+    }
+}
+
+impl From<SolidColor> for Color {
+    fn from(color: SolidColor) -> Self {
+        match color {
+            SolidColor::Black => Self::Black,
+            SolidColor::White => Self::White,
+            SolidColor::Clear => Self::Clear,
+            SolidColor::Xor => Self::Xor,
+        }
+    }
+}
+
+impl From<Pattern> for Color {
+    fn from(pattern: Pattern) -> Self {
+        Self::Pattern(pattern)
+    }
+}
 
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -0,0 +1,510 @@
+use crate::{
+    bitmap::Bitmap,
+    dither::{dither, DitherType},
+    error::{Error, Result},
+    file::{FileOptions, FileSystem},
+    gfx::Color,
+    png,
+};
+use alloc::{format, vec, vec::Vec};
+use core::ffi::CStr;
+
+// Decoding takes a few bytes per pixel on top of the bitmap, so images are limited to four
+// screens' worth to stay well within the Playdate's 16 MB
+pub(crate) const MAX_PIXELS: usize = 4 * 400 * 240;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AlphaMode {
+    // Decodes every pixel as opaque, without a mask
+    Ignore,
+    // Pixels are opaque when their alpha is at least this value
+    Threshold(u8),
+    // Alpha is dithered into the mask like the greyscale is into the pixels
+    Dither,
+}
+
+impl Default for AlphaMode {
+    fn default() -> Self {
+        Self::Threshold(128)
+    }
+}
+
+// Decodes PNG, BMP and PBM/PGM images from memory. Colors are converted to greyscale and
+// dithered down to 1 bit, and images with transparency get a mask.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ImageDecoder {
+    dither: DitherType,
+    alpha: AlphaMode,
+}
+
+impl ImageDecoder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn dither(mut self, dither: DitherType) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    pub fn alpha(mut self, alpha: AlphaMode) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn decode(&self, data: &[u8]) -> Result<Bitmap> {
+        let image = if data.starts_with(&png::SIGNATURE) {
+            png::decode(data)?
+        } else if data.starts_with(b"BM") {
+            decode_bmp(data)?
+        } else if data.starts_with(b"P") {
+            decode_pnm(data)?
+        } else {
+            return Err(Error::new("unsupported image format"));
+        };
+
+        Ok(self.bitmap(image))
+    }
+
+    fn bitmap(&self, image: Image) -> Bitmap {
+        let Image {
+            width,
+            height,
+            mut grey,
+            alpha,
+        } = image;
+
+        let alpha = alpha.filter(|_| self.alpha != AlphaMode::Ignore);
+
+        // Clear bitmaps are created with a mask
+        let bg_color = if alpha.is_some() {
+            Color::Clear
+        } else {
+            Color::White
+        };
+        let mut bitmap = Bitmap::new(width as i32, height as i32, bg_color);
+        let mut pixels = bitmap.pixels_mut();
+
+        dither(&mut grey, width, self.dither, |x, y, white| {
+            pixels.set_white(x, y, white);
+        });
+
+        if let (Some(mut alpha), Some(mut mask)) = (alpha, pixels.mask_mut()) {
+            let mut set = |x, y, opaque| {
+                mask.set(x, y, opaque);
+            };
+
+            match self.alpha {
+                AlphaMode::Threshold(threshold) => {
+                    for (i, &value) in alpha.iter().enumerate() {
+                        set(
+                            (i % width) as i32,
+                            (i / width) as i32,
+                            value >= threshold as i16,
+                        );
+                    }
+                }
+                _ => dither(&mut alpha, width, self.dither, set),
+            }
+        }
+
+        bitmap
+    }
+}
+
+// Paths are relative to the game's data directory, and existing files are replaced
+impl Bitmap {
+    // The mask, if any, is saved as transparency
+    pub fn encode_png(&self) -> Vec<u8> {
+        png::encode(&self.pixels())
+    }
+
+    // PBM has no transparency, so the mask is ignored
+    pub fn encode_pbm(&self) -> Vec<u8> {
+        let pixels = self.pixels();
+        let (width, height) = (pixels.width(), pixels.height());
+        let row_bytes = (width as usize).div_ceil(8);
+        let mut out = Vec::from(format!("P4\n{} {}\n", width, height));
+
+        for row in pixels.rows() {
+            // In bitmaps 1 is black, and the padding at the end of each row is zeroed
+            let start = out.len();
+            out.extend(row[..row_bytes].iter().map(|byte| !byte));
+            if width % 8 > 0 {
+                out[start + row_bytes - 1] &= 0xff << (8 - width % 8);
+            }
+        }
+
+        out
+    }
+
+    pub fn save_png(&self, path: &CStr) -> Result<()> {
+        save(path, &self.encode_png())
+    }
+
+    pub fn save_pbm(&self, path: &CStr) -> Result<()> {
+        save(path, &self.encode_pbm())
+    }
+}
+
+fn save(path: &CStr, data: &[u8]) -> Result<()> {
+    let mut file = FileSystem::new().open(path, FileOptions::WRITE)?;
+    file.write(data)
+}
+
+// A decoded image with greyscale and alpha values from 0 to 255, stored as i16 so that
+// they can be dithered in place
+pub(crate) struct Image {
+    width: usize,
+    height: usize,
+    grey: Vec<i16>,
+    alpha: Option<Vec<i16>>,
+}
+
+impl Image {
+    pub(crate) fn new(width: usize, height: usize, has_alpha: bool) -> Result<Self> {
+        let pixels = width.saturating_mul(height);
+        if width == 0 || height == 0 || pixels > MAX_PIXELS {
+            return Err(Error::new(format!(
+                "unsupported image size {}x{}",
+                width, height
+            )));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            grey: vec![0; width * height],
+            alpha: has_alpha.then(|| vec![255; width * height]),
+        })
+    }
+
+    #[cfg(test)]
+    pub(crate) fn get(&self, x: usize, y: usize) -> (i16, Option<i16>) {
+        let i = y * self.width + x;
+        (self.grey[i], self.alpha.as_ref().map(|values| values[i]))
+    }
+
+    pub(crate) fn set(&mut self, x: usize, y: usize, grey: u8, alpha: u8) {
+        let i = y * self.width + x;
+        self.grey[i] = grey as i16;
+        if let Some(values) = self.alpha.as_mut() {
+            values[i] = alpha as i16;
+        }
+    }
+}
+
+pub(crate) fn luminance(r: u8, g: u8, b: u8) -> u8 {
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| Error::new("invalid BMP: truncated header"))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| Error::new("invalid BMP: truncated header"))
+}
+
+// Uncompressed 1, 4, 8, 24 and 32 bit BMPs
+fn decode_bmp(data: &[u8]) -> Result<Image> {
+    let pixel_offset = read_u32(data, 10)? as usize;
+    let header_size = read_u32(data, 14)? as usize;
+    let width = read_u32(data, 18)? as i32;
+    let height = read_u32(data, 22)? as i32;
+    let bits = read_u16(data, 28)? as usize;
+    let compression = read_u32(data, 30)?;
+    let palette_len = match read_u32(data, 46)? {
+        0 if bits <= 8 => 1 << bits,
+        len => len as usize,
+    };
+
+    // 3 is BI_BITFIELDS, which is assumed to use the usual BGRA layout
+    if compression != 0 && !(compression == 3 && bits == 32) {
+        return Err(Error::new(
+            "invalid BMP: compressed images are not supported",
+        ));
+    }
+
+    if !matches!(bits, 1 | 4 | 8 | 24 | 32) {
+        return Err(Error::new(format!(
+            "invalid BMP: unsupported bit depth {}",
+            bits
+        )));
+    }
+
+    // Rows are stored bottom up unless the height is negative
+    let top_down = height < 0;
+    let (width, height) = (
+        width.unsigned_abs() as usize,
+        height.unsigned_abs() as usize,
+    );
+    let palette = header_size
+        .checked_add(14)
+        .and_then(|start| data.get(start..))
+        .map(|palette| &palette[..(palette_len * 4).min(palette.len())])
+        .unwrap_or(&[]);
+
+    // Many encoders leave the alpha channel of 32 bit images empty
+    let has_alpha = bits == 32
+        && data
+            .get(pixel_offset..)
+            .is_some_and(|pixels| pixels.iter().skip(3).step_by(4).any(|&a| a != 0));
+
+    // The size is checked here, which keeps the row size from overflowing
+    let mut image = Image::new(width, height, has_alpha)?;
+    let row_bytes = (width * bits).div_ceil(32) * 4;
+
+    for row in 0..height {
+        let y = if top_down { row } else { height - 1 - row };
+        let pixels = row
+            .checked_mul(row_bytes)
+            .and_then(|offset| offset.checked_add(pixel_offset))
+            .and_then(|start| data.get(start..start.checked_add(row_bytes)?))
+            .ok_or_else(|| Error::new("invalid BMP: truncated pixel data"))?;
+
+        for x in 0..width {
+            let (grey, alpha) = match bits {
+                24 | 32 => {
+                    let p = &pixels[x * bits / 8..];
+                    let alpha = if bits == 32 { p[3] } else { 255 };
+                    (luminance(p[2], p[1], p[0]), alpha)
+                }
+                _ => {
+                    let bit = x * bits;
+                    let shift = 8 - bits - bit % 8;
+                    let index = ((pixels[bit / 8] >> shift) & ((1 << bits) - 1) as u8) as usize;
+                    let color = palette
+                        .get(index * 4..index * 4 + 3)
+                        .ok_or_else(|| Error::new("invalid BMP: palette index out of range"))?;
+                    (luminance(color[2], color[1], color[0]), 255)
+                }
+            };
+
+            image.set(x, y, grey, alpha);
+        }
+    }
+
+    Ok(image)
+}
+
+// Netpbm bitmaps (P1, P4) and greymaps (P2, P5)
+fn decode_pnm(data: &[u8]) -> Result<Image> {
+    let mut header = PnmHeader { data, pos: 2 };
+    let kind = data.get(1).copied().unwrap_or(0);
+
+    if !matches!(kind, b'1' | b'2' | b'4' | b'5') {
+        return Err(Error::new("invalid PNM: unsupported format"));
+    }
+
+    let width = header.number()?;
+    let height = header.number()?;
+    let max = match kind {
+        b'2' | b'5' => header.number()?.clamp(1, 65535),
+        _ => 1,
+    };
+
+    let mut image = Image::new(width, height, false)?;
+    let truncated = || Error::new("invalid PNM: truncated pixel data");
+
+    match kind {
+        // In bitmaps 1 is black
+        b'1' => {
+            for i in 0..width * height {
+                let white = header.bit()? == 0;
+                image.set(i % width, i / width, if white { 255 } else { 0 }, 255);
+            }
+        }
+        b'2' => {
+            for i in 0..width * height {
+                let grey = (header.number()?.min(max) * 255 / max) as u8;
+                image.set(i % width, i / width, grey, 255);
+            }
+        }
+        b'4' => {
+            // A single whitespace character separates the header from the data
+            let start = header.pos + 1;
+            let row_bytes = width.div_ceil(8);
+
+            for y in 0..height {
+                let offset = start + y * row_bytes;
+                let row = data.get(offset..offset + row_bytes).ok_or_else(truncated)?;
+
+                for x in 0..width {
+                    let black = row[x / 8] & (0x80 >> (x % 8)) != 0;
+                    image.set(x, y, if black { 0 } else { 255 }, 255);
+                }
+            }
+        }
+        _ => {
+            let start = header.pos + 1;
+            let bytes = if max > 255 { 2 } else { 1 };
+
+            for i in 0..width * height {
+                let offset = start + i * bytes;
+                let sample = data.get(offset..offset + bytes).ok_or_else(truncated)?;
+                let value = match *sample {
+                    [high, low] => u16::from_be_bytes([high, low]) as usize,
+                    [value] => value as usize,
+                    _ => unreachable!(),
+                };
+
+                image.set(
+                    i % width,
+                    i / width,
+                    (value.min(max) * 255 / max) as u8,
+                    255,
+                );
+            }
+        }
+    }
+
+    Ok(image)
+}
+
+struct PnmHeader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl PnmHeader<'_> {
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.data.get(self.pos) {
+                Some(b'#') => {
+                    while !matches!(self.data.get(self.pos), Some(b'\n') | None) {
+                        self.pos += 1;
+                    }
+                }
+                Some(c) if c.is_ascii_whitespace() => self.pos += 1,
+                _ => return,
+            }
+        }
+    }
+
+    // Reads the next decimal number, skipping whitespace and comments
+    fn number(&mut self) -> Result<usize> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let mut value: usize = 0;
+
+        while let Some(&c) = self.data.get(self.pos).filter(|c| c.is_ascii_digit()) {
+            value = value
+                .checked_mul(10)
+                .and_then(|value| value.checked_add((c - b'0') as usize))
+                .ok_or_else(|| Error::new("invalid PNM: number too large"))?;
+            self.pos += 1;
+        }
+
+        if self.pos == start {
+            return Err(Error::new("invalid PNM: expected a number"));
+        }
+
+        Ok(value)
+    }
+
+    // Plain bitmaps don't need whitespace between pixels, so they're read a digit at a time
+    fn bit(&mut self) -> Result<u8> {
+        self.skip_whitespace();
+
+        match self.data.get(self.pos) {
+            Some(&c @ (b'0' | b'1')) => {
+                self.pos += 1;
+                Ok(c - b'0')
+            }
+            _ => Err(Error::new("invalid PNM: expected 0 or 1")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 24 bit BMP with `rows` listed bottom up, each padded to 4 bytes
+    fn bmp(width: u32, height: i32, rows: &[&[u8]]) -> Vec<u8> {
+        let mut data = vec![0; 54];
+        data[..2].copy_from_slice(b"BM");
+        data[10..14].copy_from_slice(&54u32.to_le_bytes());
+        data[14..18].copy_from_slice(&40u32.to_le_bytes());
+        data[18..22].copy_from_slice(&width.to_le_bytes());
+        data[22..26].copy_from_slice(&height.to_le_bytes());
+        data[28..30].copy_from_slice(&24u16.to_le_bytes());
+        for row in rows {
+            data.extend_from_slice(row);
+        }
+        data
+    }
+
+    #[test]
+    fn decodes_bmp() {
+        let data = bmp(
+            2,
+            2,
+            &[
+                &[0, 0, 0, 255, 255, 255, 0, 0],
+                &[255, 255, 255, 0, 0, 0, 0, 0],
+            ],
+        );
+        let image = decode_bmp(&data).unwrap();
+
+        assert_eq!((image.width, image.height), (2, 2));
+        assert_eq!(image.get(0, 0), (255, None));
+        assert_eq!(image.get(1, 0), (0, None));
+        assert_eq!(image.get(0, 1), (0, None));
+        assert_eq!(image.get(1, 1), (255, None));
+
+        // Negative heights are stored top down
+        let data = bmp(1, -2, &[&[255, 255, 255, 0], &[0, 0, 0, 0]]);
+        let image = decode_bmp(&data).unwrap();
+        assert_eq!(image.get(0, 0), (255, None));
+        assert_eq!(image.get(0, 1), (0, None));
+    }
+
+    #[test]
+    fn rejects_bad_bmps() {
+        assert!(decode_bmp(&[]).is_err());
+
+        let data = bmp(2, 2, &[&[0; 8]]);
+        assert!(decode_bmp(&data).is_err());
+
+        let mut data = bmp(1, 1, &[&[0; 4]]);
+        data[10..14].copy_from_slice(&u32::MAX.to_le_bytes());
+        data[14..18].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(decode_bmp(&data).is_err());
+
+        let data = bmp(100_000, 100_000, &[]);
+        assert!(decode_bmp(&data).is_err());
+    }
+
+    #[test]
+    fn decodes_pnm() {
+        let image = decode_pnm(b"P1\n# comment\n2 1\n1 0\n").unwrap();
+        assert_eq!(image.get(0, 0), (0, None));
+        assert_eq!(image.get(1, 0), (255, None));
+
+        let image = decode_pnm(b"P2 2 1 4 0 4").unwrap();
+        assert_eq!(image.get(0, 0), (0, None));
+        assert_eq!(image.get(1, 0), (255, None));
+
+        let image = decode_pnm(b"P4 9 1\n\x80\x80").unwrap();
+        assert_eq!(image.get(0, 0), (0, None));
+        assert_eq!(image.get(1, 0), (255, None));
+        assert_eq!(image.get(8, 0), (0, None));
+
+        assert!(decode_pnm(b"P4 9 1\n\x80").is_err());
+        assert!(decode_pnm(b"P3 1 1 1 0 0 0").is_err());
+    }
+
+    #[test]
+    fn limits_image_size() {
+        assert!(Image::new(400, 240, true).is_ok());
+        assert!(Image::new(0, 240, false).is_err());
+        assert!(Image::new(MAX_PIXELS + 1, 1, false).is_err());
+        assert!(Image::new(usize::MAX, 2, false).is_err());
+    }
+}
@@ -0,0 +1,735 @@
+use crate::{
+    bitmap::BitmapTable,
+    error::{Error, Result},
+    file::FileSystem,
+    geometry::{IntSize, Rect},
+    json::{self, Value},
+    sprite::{GameObject, SpriteAPI},
+    tile_map::TileMap,
+    zlib,
+};
+use alloc::{
+    boxed::Box,
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::ffi::CStr;
+
+// Tiled keeps flip and rotation flags in the top bits of each tile ID
+const TILED_FLAGS: u32 = 0xf000_0000;
+
+// Each tile takes 4 bytes, so this keeps a layer to 4 MB of the Playdate's 16
+const MAX_LAYER_TILES: usize = 1 << 20;
+
+fn error(message: &str) -> Error {
+    Error::new(format!("invalid level: {}", message))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    // Also used for colors, file paths and enum values
+    String(String),
+    // Anything else, such as LDtk arrays and points, is left as it was in the file
+    Json(Value),
+}
+
+impl PropertyValue {
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(n) => Some(*n),
+            Self::Float(n) => Some(*n as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(n) => Some(*n as f64),
+            Self::Float(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    // Converts a JSON value, using the editor's type name when there is one
+    fn from_json(kind: &str, value: &Value) -> Self {
+        match (kind, value) {
+            (_, Value::Bool(b)) => Self::Bool(*b),
+            ("float" | "Float", Value::Number(n)) => Self::Float(*n),
+            (_, Value::Number(n)) if *n as i64 as f64 == *n => Self::Int(*n as i64),
+            (_, Value::Number(n)) => Self::Float(*n),
+            (_, Value::String(s)) => Self::String(s.clone()),
+            _ => Self::Json(value.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Properties(Vec<(String, PropertyValue)>);
+
+impl Properties {
+    pub fn get(&self, name: &str) -> Option<&PropertyValue> {
+        self.0.iter().find(|(k, _)| k == name).map(|(_, v)| v)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &PropertyValue)> + '_ {
+        self.0.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // Tiled stores properties as `[{ "name", "type", "value" }]`
+    fn from_tiled(value: &Value) -> Self {
+        let properties = value
+            .as_array()
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|property| {
+                let name = property["name"].as_str()?;
+                let kind = property["type"].as_str().unwrap_or("string");
+                let value = PropertyValue::from_json(kind, &property["value"]);
+                Some((name.to_string(), value))
+            });
+
+        Self(properties.collect())
+    }
+
+    // LDtk stores them as `[{ "__identifier", "__type", "__value" }]`
+    fn from_ldtk(value: &Value) -> Self {
+        let properties = value.as_array().unwrap_or(&[]).iter().filter_map(|field| {
+            let name = field["__identifier"].as_str()?;
+            let kind = field["__type"].as_str().unwrap_or("String");
+            let value = PropertyValue::from_json(kind, &field["__value"]);
+            Some((name.to_string(), value))
+        });
+
+        Self(properties.collect())
+    }
+}
+
+// A Tiled object or LDtk entity, for the game to turn into a sprite
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelObject {
+    // Tiled's numeric ID as a string, or LDtk's IID
+    pub id: String,
+    pub name: String,
+    // Tiled's class (or type in older versions), or LDtk's entity identifier
+    pub kind: String,
+    pub layer: String,
+    // In level pixels, from the top left corner
+    pub bounds: Rect,
+    // The tileset index for Tiled tile objects
+    pub tile: Option<u16>,
+    pub properties: Properties,
+}
+
+pub struct TileLayer {
+    pub name: String,
+    pub visible: bool,
+    pub map: TileMap,
+    // LDtk int grid values row by row, with 0 for empty cells. Empty for other layers.
+    pub int_grid: Vec<i32>,
+}
+
+// Layers are ordered bottom to top
+pub struct Level {
+    pub size: IntSize,
+    pub tile_size: IntSize,
+    pub layers: Vec<TileLayer>,
+    pub objects: Vec<LevelObject>,
+    pub properties: Properties,
+}
+
+impl Level {
+    pub fn layer(&self, name: &str) -> Option<&TileLayer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut TileLayer> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    pub fn objects_of_kind<'a>(&'a self, kind: &'a str) -> impl Iterator<Item = &'a LevelObject> {
+        self.objects
+            .iter()
+            .filter(move |object| object.kind == kind)
+    }
+
+    // Adds a sprite for every object that `create` returns a game object for
+    pub fn spawn<T, F>(&self, sprites: &mut SpriteAPI<T>, mut create: F)
+    where
+        F: FnMut(&LevelObject) -> Option<Box<dyn GameObject<T>>>,
+    {
+        for object in &self.objects {
+            if let Some(game_object) = create(object) {
+                sprites.new_sprite(game_object);
+            }
+        }
+    }
+}
+
+// Loads levels made in Tiled (as JSON maps) or LDtk. Tile layers draw from a single bitmap
+// table whose frames are the same size as the level's tiles, with each tile's index in the
+// editor's tileset being its index in the table.
+pub struct LevelLoader {
+    tileset: Rc<BitmapTable>,
+}
+
+impl LevelLoader {
+    pub fn new(tileset: Rc<BitmapTable>) -> Self {
+        Self { tileset }
+    }
+
+    pub fn load_tiled(&self, fs: &FileSystem, path: &CStr) -> Result<Level> {
+        self.tiled_from_json(&json::parse_bytes(&fs.read_file(path)?)?)
+    }
+
+    // Loads the level named `level` from an LDtk project
+    pub fn load_ldtk(&self, fs: &FileSystem, path: &CStr, level: &str) -> Result<Level> {
+        self.ldtk_from_json(&json::parse_bytes(&fs.read_file(path)?)?, level)
+    }
+
+    pub fn tiled_from_json(&self, map: &Value) -> Result<Level> {
+        if map["orientation"]
+            .as_str()
+            .is_some_and(|o| o != "orthogonal")
+        {
+            return Err(error("only orthogonal Tiled maps are supported"));
+        }
+
+        if map["infinite"].as_bool() == Some(true) {
+            return Err(error("infinite Tiled maps are not supported"));
+        }
+
+        let tile_size = IntSize::new(int(map, "tilewidth")?, int(map, "tileheight")?);
+        let (columns, rows) = (int(map, "width")?, int(map, "height")?);
+        if tile_size.is_empty() {
+            return Err(error("bad Tiled tile size"));
+        }
+        self.check_tile_size(tile_size)?;
+
+        let first_id = tiled_first_id(map)?;
+        layer_len(columns, rows)?;
+        let size = columns
+            .checked_mul(tile_size.width)
+            .zip(rows.checked_mul(tile_size.height))
+            .map(|(width, height)| IntSize::new(width, height))
+            .ok_or_else(|| error("level too large"))?;
+
+        let mut level = Level {
+            size,
+            tile_size,
+            layers: Vec::new(),
+            objects: Vec::new(),
+            properties: Properties::from_tiled(&map["properties"]),
+        };
+
+        let layers = map["layers"].as_array().unwrap_or(&[]);
+        self.tiled_layers(layers, first_id, &mut level)?;
+        Ok(level)
+    }
+
+    fn tiled_layers(
+        &self,
+        layers: &[Value],
+        first_id: Option<u32>,
+        level: &mut Level,
+    ) -> Result<()> {
+        for layer in layers {
+            let name = layer["name"].as_str().unwrap_or_default();
+            let visible = layer["visible"].as_bool().unwrap_or(true);
+
+            match layer["type"].as_str() {
+                Some("tilelayer") => {
+                    let columns = int(layer, "width")?;
+                    let tiles = tiled_layer_data(layer)?
+                        .into_iter()
+                        .map(|id| tiled_tile(id, first_id))
+                        .collect::<Result<_>>()?;
+
+                    level.layers.push(TileLayer {
+                        name: name.to_string(),
+                        visible,
+                        map: TileMap::from_tiles(self.tileset.clone(), columns, tiles),
+                        int_grid: Vec::new(),
+                    });
+                }
+                Some("objectgroup") => {
+                    for object in layer["objects"].as_array().unwrap_or(&[]) {
+                        level.objects.push(tiled_object(object, name, first_id)?);
+                    }
+                }
+                Some("group") => {
+                    let layers = layer["layers"].as_array().unwrap_or(&[]);
+                    self.tiled_layers(layers, first_id, level)?;
+                }
+                // Image layers have nothing to load
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn ldtk_from_json(&self, project: &Value, level: &str) -> Result<Level> {
+        let source = project["levels"]
+            .as_array()
+            .unwrap_or(&[])
+            .iter()
+            .find(|source| source["identifier"].as_str() == Some(level))
+            .ok_or_else(|| error("no LDtk level with that identifier"))?;
+
+        // Projects saved with separate level files leave the layers out
+        let layers = source["layerInstances"]
+            .as_array()
+            .ok_or_else(|| error("LDtk levels in separate files are not supported"))?;
+
+        let grid_size = layers
+            .iter()
+            .find(|layer| layer["__type"].as_str() != Some("Entities"))
+            .map(|layer| int(layer, "__gridSize"))
+            .unwrap_or_else(|| int(project, "defaultGridSize"))?;
+        if grid_size <= 0 {
+            return Err(error("bad LDtk grid size"));
+        }
+        let tile_size = IntSize::new(grid_size, grid_size);
+
+        let mut level = Level {
+            size: IntSize::new(int(source, "pxWid")?, int(source, "pxHei")?),
+            tile_size,
+            layers: Vec::new(),
+            objects: Vec::new(),
+            properties: Properties::from_ldtk(&source["fieldInstances"]),
+        };
+
+        // LDtk lists layers top to bottom
+        for layer in layers.iter().rev() {
+            let name = layer["__identifier"].as_str().unwrap_or_default();
+            let visible = layer["visible"].as_bool().unwrap_or(true);
+
+            let tiles_key = match layer["__type"].as_str() {
+                Some("Entities") => {
+                    for entity in layer["entityInstances"].as_array().unwrap_or(&[]) {
+                        level.objects.push(ldtk_entity(entity, name)?);
+                    }
+                    continue;
+                }
+                Some("Tiles") => "gridTiles",
+                _ => "autoLayerTiles",
+            };
+
+            let (columns, rows) = (int(layer, "__cWid")?, int(layer, "__cHei")?);
+            let cell_size = int(layer, "__gridSize")?;
+            if cell_size <= 0 {
+                return Err(error("bad LDtk grid size"));
+            }
+            self.check_tile_size(IntSize::new(cell_size, cell_size))?;
+
+            let mut tiles = vec![None; layer_len(columns, rows)?];
+            for tile in layer[tiles_key].as_array().unwrap_or(&[]) {
+                let (x, y) = (tile["px"][0].as_i64(), tile["px"][1].as_i64());
+                let (Some(x), Some(y), Some(id)) = (x, y, tile["t"].as_i64()) else {
+                    return Err(error("bad LDtk tile"));
+                };
+
+                let (column, row) = (x as i32 / cell_size, y as i32 / cell_size);
+                if (0..columns).contains(&column) && (0..rows).contains(&row) {
+                    // Later tiles in the same cell are drawn over earlier ones
+                    tiles[(row * columns + column) as usize] = Some(tile_index(id)?);
+                }
+            }
+
+            let int_grid = layer["intGridCsv"]
+                .as_array()
+                .unwrap_or(&[])
+                .iter()
+                .map(|value| value.as_i64().unwrap_or(0) as i32)
+                .collect();
+
+            level.layers.push(TileLayer {
+                name: name.to_string(),
+                visible,
+                map: TileMap::from_tiles(self.tileset.clone(), columns, tiles),
+                int_grid,
+            });
+        }
+
+        Ok(level)
+    }
+
+    fn check_tile_size(&self, tile_size: IntSize) -> Result<()> {
+        match self.tileset.frame_size() {
+            Some(frame_size) if frame_size != tile_size => Err(Error::new(format!(
+                "invalid level: tiles are {}x{} but the tileset's are {}x{}",
+                tile_size.width, tile_size.height, frame_size.width, frame_size.height
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn int(value: &Value, key: &str) -> Result<i32> {
+    value[key]
+        .as_i64()
+        .map(|n| n as i32)
+        .ok_or_else(|| Error::new(format!("invalid level: missing {}", key)))
+}
+
+fn layer_len(columns: i32, rows: i32) -> Result<usize> {
+    if columns < 0 || rows < 0 {
+        return Err(error("bad layer size"));
+    }
+
+    (columns as usize)
+        .checked_mul(rows as usize)
+        .filter(|&len| len <= MAX_LAYER_TILES)
+        .ok_or_else(|| error("layer too large"))
+}
+
+fn tile_index(id: i64) -> Result<u16> {
+    u16::try_from(id).map_err(|_| error("tile index out of range"))
+}
+
+// Tile indices are only meaningful for the one bitmap table the loader draws from
+fn tiled_first_id(map: &Value) -> Result<Option<u32>> {
+    match map["tilesets"].as_array().unwrap_or(&[]) {
+        [] => Ok(None),
+        [tileset] => Ok(Some(int(tileset, "firstgid")? as u32)),
+        _ => Err(error(
+            "Tiled maps with more than one tileset are not supported",
+        )),
+    }
+}
+
+// Converts a global tile ID to an index in the tileset, with 0 being an empty tile
+fn tiled_tile(id: u32, first_id: Option<u32>) -> Result<Option<u16>> {
+    let id = id & !TILED_FLAGS;
+    if id == 0 {
+        return Ok(None);
+    }
+
+    let first_id = first_id
+        .filter(|&first_id| first_id <= id)
+        .ok_or_else(|| error("tile ID without a tileset"))?;
+
+    tile_index((id - first_id) as i64).map(Some)
+}
+
+// Tile data is either an array of IDs, or base64 encoded little endian IDs that may be
+// zlib compressed
+fn tiled_layer_data(layer: &Value) -> Result<Vec<u32>> {
+    let len = layer_len(int(layer, "width")?, int(layer, "height")?)?;
+    let ids = tiled_ids(layer, len)?;
+
+    if ids.len() != len {
+        return Err(error("tile layer data doesn't match its size"));
+    }
+
+    Ok(ids)
+}
+
+fn tiled_ids(layer: &Value, len: usize) -> Result<Vec<u32>> {
+    if let Some(ids) = layer["data"].as_array() {
+        return ids
+            .iter()
+            .map(|id| {
+                // as_i64 would truncate fractional IDs instead of rejecting them
+                id.as_f64()
+                    .filter(|&id| id >= 0.0 && id <= u32::MAX as f64 && id as u32 as f64 == id)
+                    .map(|id| id as u32)
+                    .ok_or_else(|| error("bad tile ID"))
+            })
+            .collect();
+    }
+
+    let text = layer["data"]
+        .as_str()
+        .ok_or_else(|| error("missing tile layer data"))?;
+    let mut bytes = base64_decode(text)?;
+
+    match layer["compression"].as_str().unwrap_or_default() {
+        "" => {}
+        "zlib" => bytes = zlib::decompress(&bytes, len * 4)?,
+        _ => return Err(error("only zlib compressed Tiled layers are supported")),
+    }
+
+    if bytes.len() % 4 > 0 {
+        return Err(error("bad tile layer data"));
+    }
+
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+fn tiled_object(object: &Value, layer: &str, first_id: Option<u32>) -> Result<LevelObject> {
+    let number = |key: &str| object[key].as_f64().unwrap_or(0.0) as f32;
+    let (width, height) = (number("width"), number("height"));
+    let tile = match object["gid"].as_i64() {
+        Some(id) => tiled_tile(id as u32, first_id)?,
+        None => None,
+    };
+
+    // Tile objects are positioned by their bottom left corner
+    let y = if tile.is_some() {
+        number("y") - height
+    } else {
+        number("y")
+    };
+
+    let kind = object["class"]
+        .as_str()
+        .filter(|kind| !kind.is_empty())
+        .or(object["type"].as_str())
+        .unwrap_or_default();
+
+    Ok(LevelObject {
+        id: object["id"].as_i64().unwrap_or(0).to_string(),
+        name: object["name"].as_str().unwrap_or_default().to_string(),
+        kind: kind.to_string(),
+        layer: layer.to_string(),
+        bounds: Rect::new(number("x"), y, width, height),
+        tile,
+        properties: Properties::from_tiled(&object["properties"]),
+    })
+}
+
+fn ldtk_entity(entity: &Value, layer: &str) -> Result<LevelObject> {
+    let (x, y) = (entity["px"][0].as_f64(), entity["px"][1].as_f64());
+    let (Some(x), Some(y)) = (x, y) else {
+        return Err(error("bad LDtk entity position"));
+    };
+
+    let width = entity["width"].as_f64().unwrap_or(0.0) as f32;
+    let height = entity["height"].as_f64().unwrap_or(0.0) as f32;
+
+    // Entities are positioned by their pivot, which is a fraction of their size
+    let pivot_x = entity["__pivot"][0].as_f64().unwrap_or(0.0) as f32;
+    let pivot_y = entity["__pivot"][1].as_f64().unwrap_or(0.0) as f32;
+
+    let kind = entity["__identifier"].as_str().unwrap_or_default();
+
+    Ok(LevelObject {
+        id: entity["iid"].as_str().unwrap_or_default().to_string(),
+        name: String::new(),
+        kind: kind.to_string(),
+        layer: layer.to_string(),
+        bounds: Rect::new(
+            x as f32 - pivot_x * width,
+            y as f32 - pivot_y * height,
+            width,
+            height,
+        ),
+        tile: None,
+        properties: Properties::from_ldtk(&entity["fieldInstances"]),
+    })
+}
+
+fn base64_decode(text: &str) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() / 4 * 3);
+    let (mut buffer, mut bits) = (0u32, 0);
+
+    for c in text.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ if c.is_ascii_whitespace() => continue,
+            _ => return Err(error("bad base64 tile data")),
+        };
+
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Value {
+        json::parse(text).unwrap()
+    }
+
+    fn base64_encode(bytes: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut out = String::new();
+
+        for chunk in bytes.chunks(3) {
+            let buffer = chunk.iter().enumerate().fold(0u32, |buffer, (i, &byte)| {
+                buffer | (byte as u32) << (16 - i * 8)
+            });
+
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(ALPHABET[(buffer >> (18 - i * 6)) as usize & 63] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+
+        out
+    }
+
+    #[test]
+    fn decodes_base64() {
+        assert_eq!(base64_decode("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(base64_decode("aGVs\nbG8h").unwrap(), b"hello!");
+        assert_eq!(base64_decode("").unwrap(), b"");
+        assert!(base64_decode("aGV*").is_err());
+
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(base64_decode(&base64_encode(&bytes)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn reads_tiled_layer_data() {
+        let layer = parse(r#"{"width": 2, "height": 1, "data": [0, 3]}"#);
+        assert_eq!(tiled_layer_data(&layer).unwrap(), [0, 3]);
+
+        // [1, 2] as little endian u32s
+        let layer = parse(r#"{"width": 2, "height": 1, "data": "AQAAAAIAAAA="}"#);
+        assert_eq!(tiled_layer_data(&layer).unwrap(), [1, 2]);
+
+        let data = base64_encode(&zlib::compress(&[5, 0, 0, 0, 6, 0, 0, 0]));
+        let layer = Value::Object(vec![
+            ("width".to_string(), Value::Number(1.0)),
+            ("height".to_string(), Value::Number(2.0)),
+            ("data".to_string(), Value::String(data)),
+            ("compression".to_string(), Value::String("zlib".to_string())),
+        ]);
+        assert_eq!(tiled_layer_data(&layer).unwrap(), [5, 6]);
+    }
+
+    #[test]
+    fn rejects_bad_tiled_layer_data() {
+        for text in [
+            r#"{"width": 3, "height": 1, "data": [0, 3]}"#,
+            r#"{"width": 2, "height": 1, "data": [0, "3"]}"#,
+            r#"{"width": 2, "height": 1, "data": [0, 1.5]}"#,
+            r#"{"width": 2, "height": 1, "data": [0, -1]}"#,
+            r#"{"width": 1, "height": 1, "data": "AQAA"}"#,
+            r#"{"width": 2, "height": 1, "data": "AQAAAAIAAAA=", "compression": "gzip"}"#,
+            r#"{"width": 2, "height": 1}"#,
+        ] {
+            assert!(tiled_layer_data(&parse(text)).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn maps_tiled_ids() {
+        assert_eq!(tiled_tile(0, Some(1)).unwrap(), None);
+        assert_eq!(tiled_tile(1, Some(1)).unwrap(), Some(0));
+        assert_eq!(tiled_tile(0x8000_0005, Some(1)).unwrap(), Some(4));
+        assert!(tiled_tile(5, None).is_err());
+        assert!(tiled_tile(5, Some(10)).is_err());
+
+        let map = parse(r#"{"tilesets": [{"firstgid": 1}]}"#);
+        assert_eq!(tiled_first_id(&map).unwrap(), Some(1));
+        let map = parse(r#"{"tilesets": [{"firstgid": 1}, {"firstgid": 10}]}"#);
+        assert!(tiled_first_id(&map).is_err());
+        assert_eq!(tiled_first_id(&parse("{}")).unwrap(), None);
+    }
+
+    #[test]
+    fn limits_layer_size() {
+        assert_eq!(layer_len(0, 5).unwrap(), 0);
+        assert_eq!(layer_len(100, 200).unwrap(), 20_000);
+        assert!(layer_len(-1, 5).is_err());
+        assert!(layer_len(i32::MAX, i32::MAX).is_err());
+        assert!(layer_len(MAX_LAYER_TILES as i32 + 1, 1).is_err());
+
+        let layer = parse(r#"{"width": 2000000000, "height": 2000000000, "data": []}"#);
+        assert!(tiled_layer_data(&layer).is_err());
+    }
+
+    #[test]
+    fn reads_tiled_objects() {
+        let object = parse(
+            r#"{"id": 7, "name": "door", "type": "Door", "x": 16, "y": 32, "width": 16,
+                "height": 8, "gid": 3, "properties": [
+                    {"name": "locked", "type": "bool", "value": true},
+                    {"name": "speed", "type": "float", "value": 2},
+                    {"name": "key", "type": "string", "value": "red"}
+                ]}"#,
+        );
+        let object = tiled_object(&object, "things", Some(1)).unwrap();
+
+        assert_eq!(object.id, "7");
+        assert_eq!(object.name, "door");
+        assert_eq!(object.kind, "Door");
+        assert_eq!(object.layer, "things");
+        // Tile objects are positioned by their bottom left corner
+        assert_eq!(object.bounds, Rect::new(16.0, 24.0, 16.0, 8.0));
+        assert_eq!(object.tile, Some(2));
+
+        let properties = &object.properties;
+        assert_eq!(
+            properties.get("locked").and_then(|p| p.as_bool()),
+            Some(true)
+        );
+        assert_eq!(properties.get("speed"), Some(&PropertyValue::Float(2.0)));
+        assert_eq!(properties.get("key").and_then(|p| p.as_str()), Some("red"));
+    }
+
+    #[test]
+    fn reads_ldtk_entities() {
+        let entity = parse(
+            r#"{"iid": "abc", "__identifier": "Player", "px": [32, 48], "width": 16,
+                "height": 16, "__pivot": [0.5, 1], "fieldInstances": [
+                    {"__identifier": "lives", "__type": "Int", "__value": 3},
+                    {"__identifier": "path", "__type": "Array<Point>", "__value": []}
+                ]}"#,
+        );
+        let object = ldtk_entity(&entity, "Entities").unwrap();
+
+        assert_eq!(object.id, "abc");
+        assert_eq!(object.kind, "Player");
+        assert_eq!(object.bounds, Rect::new(24.0, 32.0, 16.0, 16.0));
+
+        let properties = &object.properties;
+        assert_eq!(properties.get("lives"), Some(&PropertyValue::Int(3)));
+        assert_eq!(
+            properties.get("path"),
+            Some(&PropertyValue::Json(Value::Array(vec![])))
+        );
+
+        assert!(ldtk_entity(&parse(r#"{"px": [1]}"#), "Entities").is_err());
+    }
+}
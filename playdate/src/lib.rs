@@ -6,33 +6,83 @@ extern crate alloc;
 #[macro_use]
 mod macros;
 
+mod animation;
 mod bitmap;
+mod console;
+mod context;
 mod display;
+mod dither;
+pub mod easing;
+mod effects;
 mod error;
 mod file;
 mod font;
+mod frame;
+mod geometry;
 mod gfx;
+mod image;
+pub mod json;
+mod level;
+mod localization;
+mod math;
+mod memory;
+mod menu_image;
+mod nine_slice;
+mod path;
+mod pattern;
+mod pixels;
+mod png;
+mod rich_text;
 pub mod rng;
 mod sprite;
 mod string;
 mod system;
+mod task;
+mod text;
+mod tile_map;
+mod time;
+mod transform;
+mod tween;
+mod zlib;
 
 use alloc::boxed::Box;
-use core::{ffi::c_void, marker::PhantomData, ptr::null_mut};
-use libc_alloc::LibcAlloc;
+use core::{ffi::c_void, future::Future, marker::PhantomData, ptr::null_mut};
+use memory::TrackingAlloc;
 use playdate_sys::PlaydateAPI;
 
+pub use animation::*;
 pub use bitmap::*;
+pub use console::*;
+pub use context::ContextGuard;
 pub use display::*;
+pub use dither::DitherType;
 pub use file::*;
 pub use font::*;
+pub use frame::*;
+pub use geometry::*;
 pub use gfx::*;
+pub use image::{AlphaMode, ImageDecoder};
+pub use level::*;
+pub use localization::*;
+pub use memory::*;
+pub use menu_image::{MENU_IMAGE_HEIGHT, MENU_IMAGE_VISIBLE_WIDTH, MENU_IMAGE_WIDTH};
+pub use nine_slice::*;
+pub use path::*;
+pub use pattern::*;
+pub use pixels::{Mask, MaskMut, Pixels, PixelsMut};
 pub use playdate_sys::libc;
+pub use rich_text::*;
 pub use sprite::*;
 pub use system::*;
+pub use task::*;
+pub use text::*;
+pub use tile_map::*;
+pub use time::*;
+pub use transform::*;
+pub use tween::*;
 
 #[global_allocator]
-static ALLOCATOR: LibcAlloc = LibcAlloc;
+static ALLOCATOR: TrackingAlloc = TrackingAlloc;
 
 pub static mut PD: *mut PlaydateAPI = null_mut();
 static mut DATA: *mut c_void = null_mut();
@@ -65,6 +115,7 @@ where
     T: 'static,
 {
     unused: PhantomData<T>,
+    console: Console,
     display: Display,
     file: FileSystem,
     graphics: Graphics,
@@ -75,6 +126,7 @@ where
 impl<T> Playdate<T> {
     pub unsafe fn init() -> Self {
         let system = System::new();
+        let console = Console::new();
         let display = Display::new();
         let sprite = SpriteAPI::new();
         let file = FileSystem::new();
@@ -83,6 +135,7 @@ impl<T> Playdate<T> {
 
         Self {
             unused,
+            console,
             display,
             file,
             graphics,
@@ -119,6 +172,14 @@ impl<T> Playdate<T> {
         &mut self.system
     }
 
+    pub fn console(&self) -> &Console {
+        &self.console
+    }
+
+    pub fn console_mut(&mut self) -> &mut Console {
+        &mut self.console
+    }
+
     pub fn display(&self) -> &Display {
         &self.display
     }
@@ -146,4 +207,41 @@ impl<T> Playdate<T> {
     pub fn graphics_mut(&mut self) -> &mut Graphics {
         &mut self.graphics
     }
+
+    pub fn spawn<F>(&mut self, future: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        task::spawn(future)
+    }
+
+    pub fn poll_tasks(&mut self) {
+        task::poll_tasks()
+    }
+
+    pub fn task_count(&self) -> usize {
+        task::task_count()
+    }
+
+    // Renders into an offscreen 400x240 bitmap that is shown behind the system menu. The
+    // renderer runs again every time the game is paused so it can reflect live state; the
+    // rect passed to it is the part of the image left visible next to the menu.
+    pub fn set_menu_image_renderer<C>(&mut self, x_offset: i32, render: C)
+    where
+        C: FnMut(&mut Playdate<T>, IntRect) + 'static,
+    {
+        menu_image::set_renderer(x_offset, render)
+    }
+
+    pub fn clear_menu_image_renderer(&mut self) {
+        menu_image::clear_renderer()
+    }
+
+    pub fn render_menu_image(&mut self) {
+        menu_image::render()
+    }
+
+    pub fn draw_debug(&mut self) {
+        console::draw_debug(&mut self.graphics)
+    }
 }
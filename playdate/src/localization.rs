@@ -0,0 +1,363 @@
+use crate::{
+    error::{Error, Result},
+    file::FileSystem,
+    gfx::Graphics,
+    json::{self, Value},
+    system::{Language, System},
+};
+use alloc::{
+    collections::BTreeMap,
+    ffi::CString,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{ffi::CStr, fmt::Display, str};
+
+const BINARY_MAGIC: &[u8; 4] = b"PDST";
+
+#[derive(Clone, Debug, Default)]
+pub struct StringTable {
+    strings: BTreeMap<String, String>,
+}
+
+impl StringTable {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn load(fs: &FileSystem, path: &CStr) -> Result<Self> {
+        let bytes = fs.read_file(path)?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.starts_with(BINARY_MAGIC) {
+            Self::from_binary(bytes)
+        } else {
+            Self::from_json(&json::parse_bytes(bytes)?)
+        }
+    }
+
+    // Nested objects are flattened into dotted keys, so `{"menu": {"start": "Start"}}`
+    // is looked up as "menu.start"
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let mut table = Self::new();
+        table.insert_json("", value)?;
+        Ok(table)
+    }
+
+    fn insert_json(&mut self, prefix: &str, value: &Value) -> Result<()> {
+        let members = value
+            .as_object()
+            .ok_or_else(|| Error::new("string table must be a JSON object"))?;
+
+        for (key, value) in members {
+            let key = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+
+            match value {
+                Value::String(s) => self.insert(key, s.clone()),
+                Value::Object(_) => self.insert_json(&key, value)?,
+                _ => Err(Error::new(format!(
+                    "string table value for {} is not text",
+                    key
+                )))?,
+            }
+        }
+
+        Ok(())
+    }
+
+    // The binary format is the "PDST" magic followed by a little endian u32 entry count,
+    // then for each entry a u16 key length, the key, a u32 value length and the value
+    pub fn from_binary(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader { bytes, pos: 0 };
+
+        if reader.take(BINARY_MAGIC.len())? != BINARY_MAGIC {
+            return Err(Error::new("not a binary string table"));
+        }
+
+        let count = reader.u32()?;
+        let mut table = Self::new();
+
+        for _ in 0..count {
+            let key_len = reader.u16()? as usize;
+            let key = reader.str(key_len)?;
+            let value_len = reader.u32()? as usize;
+            let value = reader.str(value_len)?;
+            table.insert(key, value);
+        }
+
+        Ok(table)
+    }
+
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(BINARY_MAGIC);
+        out.extend_from_slice(&(self.strings.len() as u32).to_le_bytes());
+
+        for (key, value) in &self.strings {
+            out.extend_from_slice(&(key.len() as u16).to_le_bytes());
+            out.extend_from_slice(key.as_bytes());
+            out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            out.extend_from_slice(value.as_bytes());
+        }
+
+        out
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.strings.insert(key.into(), value.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings.get(key).map(|s| s.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        // A u32 length can overflow the position on the Playdate's 32 bit usize
+        let end = self.pos.checked_add(len);
+        let bytes = end
+            .and_then(|end| self.bytes.get(self.pos..end))
+            .ok_or_else(|| Error::new("string table is truncated"))?;
+
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn str(&mut self, len: usize) -> Result<&'a str> {
+        let bytes = self.take(len)?;
+        str::from_utf8(bytes).map_err(|_| Error::new("string table entry is not valid UTF-8"))
+    }
+}
+
+pub struct Localization {
+    language: Language,
+    fallback: Language,
+    tables: Vec<(Language, StringTable)>,
+}
+
+impl Localization {
+    pub fn new(language: Language) -> Self {
+        Self {
+            language,
+            fallback: Language::English,
+            tables: Vec::new(),
+        }
+    }
+
+    pub fn from_system(system: &System) -> Self {
+        Self::new(system.language())
+    }
+
+    pub fn with_fallback(mut self, fallback: Language) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn set_language(&mut self, language: Language) {
+        self.language = language;
+    }
+
+    pub fn add_table(&mut self, language: Language, table: StringTable) {
+        self.tables.retain(|(lang, _)| *lang != language);
+        self.tables.push((language, table));
+    }
+
+    // Loads `<dir>/<code>.bin` or `<dir>/<code>.json` for every language with a code.
+    // Languages without a table are skipped and resolved through the fallback instead.
+    pub fn load_tables(&mut self, fs: &FileSystem, dir: &str) -> Result<()> {
+        for language in [Language::English, Language::Japanese] {
+            let code = language.code().unwrap();
+
+            for extension in ["bin", "json"] {
+                let path = CString::new(format!("{}/{}.{}", dir, code, extension))
+                    .map_err(|_| Error::new("invalid string table directory"))?;
+
+                if fs.stat(&path).is_ok() {
+                    let table = StringTable::load(fs, &path)?;
+                    self.add_table(language, table);
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn table(&self, language: Language) -> Option<&StringTable> {
+        self.tables
+            .iter()
+            .find(|(lang, _)| *lang == language)
+            .map(|(_, table)| table)
+    }
+
+    // Unknown languages and missing keys fall back to the fallback language, and failing
+    // that to the key itself so that missing strings are visible rather than fatal
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.table(self.language)
+            .and_then(|table| table.get(key))
+            .or_else(|| self.table(self.fallback).and_then(|table| table.get(key)))
+            .unwrap_or(key)
+    }
+
+    // Replaces `{name}` placeholders with the matching argument; `{{` and `}}` produce
+    // literal braces and unmatched placeholders are left untouched
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let template = self.get(key);
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(i) = rest.find(['{', '}']) {
+            out.push_str(&rest[..i]);
+            let tail = &rest[i..];
+
+            if tail.starts_with("{{") || tail.starts_with("}}") {
+                out.push_str(&tail[..1]);
+                rest = &tail[2..];
+                continue;
+            }
+
+            let placeholder = tail
+                .strip_prefix('{')
+                .and_then(|inner| inner.find('}').map(|end| &inner[..end]));
+
+            let arg = placeholder.and_then(|name| args.iter().find(|(arg, _)| *arg == name));
+
+            match (placeholder, arg) {
+                (Some(name), Some((_, value))) => {
+                    out.push_str(&value.to_string());
+                    rest = &tail[name.len() + 2..];
+                }
+                _ => {
+                    out.push_str(&tail[..1]);
+                    rest = &tail[1..];
+                }
+            }
+        }
+
+        out.push_str(rest);
+        out
+    }
+
+    pub fn draw(&self, gfx: &mut Graphics, key: &str, x: i32, y: i32) {
+        gfx.draw_str(self.get(key), x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn localization() -> Localization {
+        let english = json::parse(r#"{"greeting": "Hello {name}", "menu": {"start": "Start"}}"#)
+            .and_then(|value| StringTable::from_json(&value))
+            .unwrap();
+
+        let mut japanese = StringTable::new();
+        japanese.insert("menu.start", "スタート");
+
+        let mut localization = Localization::new(Language::Japanese);
+        localization.add_table(Language::English, english);
+        localization.add_table(Language::Japanese, japanese);
+        localization
+    }
+
+    #[test]
+    fn flattens_json() {
+        let table = StringTable::from_bytes(br#"{"a": {"b": {"c": "d"}}, "e": "f"}"#).unwrap();
+        assert_eq!(table.get("a.b.c"), Some("d"));
+        assert_eq!(table.get("e"), Some("f"));
+        assert_eq!(table.len(), 2);
+
+        assert!(StringTable::from_bytes(br#"{"a": 1}"#).is_err());
+        assert!(StringTable::from_bytes(b"[]").is_err());
+    }
+
+    #[test]
+    fn round_trips_binary() {
+        let mut table = StringTable::new();
+        table.insert("key", "välue");
+        table.insert("", "empty key");
+
+        let binary = table.to_binary();
+        let loaded = StringTable::from_bytes(&binary).unwrap();
+        assert_eq!(loaded.get("key"), Some("välue"));
+        assert_eq!(loaded.get(""), Some("empty key"));
+        assert_eq!(loaded.len(), 2);
+
+        assert!(StringTable::from_binary(&binary[..binary.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn rejects_lengths_past_the_end() {
+        let mut reader = Reader {
+            bytes: b"abc",
+            pos: 1,
+        };
+        assert!(reader.take(usize::MAX).is_err());
+        assert_eq!(reader.take(2).unwrap(), b"bc");
+        assert!(reader.take(1).is_err());
+
+        let mut binary = StringTable::new().to_binary();
+        binary[4..8].copy_from_slice(&1u32.to_le_bytes());
+        binary.extend_from_slice(&[1, 0, b'k']);
+        binary.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(StringTable::from_binary(&binary).is_err());
+    }
+
+    #[test]
+    fn falls_back() {
+        let localization = localization();
+        assert_eq!(localization.get("menu.start"), "スタート");
+        assert_eq!(localization.get("greeting"), "Hello {name}");
+        assert_eq!(localization.get("missing"), "missing");
+    }
+
+    #[test]
+    fn formats_placeholders() {
+        let localization = localization();
+        assert_eq!(
+            localization.format("greeting", &[("name", &"Ada")]),
+            "Hello Ada"
+        );
+        assert_eq!(localization.format("greeting", &[]), "Hello {name}");
+        assert_eq!(
+            localization.format("{{{n}}} {x} }", &[("n", &3)]),
+            "{3} {x} }"
+        );
+    }
+}
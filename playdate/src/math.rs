@@ -0,0 +1,34 @@
+// core does not expose transcendental float functions in no_std, so borrow them from the
+// C math library that the SDK already links against.
+extern "C" {
+    fn sinf(x: f32) -> f32;
+    fn cosf(x: f32) -> f32;
+    fn tanf(x: f32) -> f32;
+    fn sqrtf(x: f32) -> f32;
+    fn powf(x: f32, y: f32) -> f32;
+    fn roundf(x: f32) -> f32;
+}
+
+pub(crate) fn sin(x: f32) -> f32 {
+    unsafe { sinf(x) }
+}
+
+pub(crate) fn cos(x: f32) -> f32 {
+    unsafe { cosf(x) }
+}
+
+pub(crate) fn tan(x: f32) -> f32 {
+    unsafe { tanf(x) }
+}
+
+pub(crate) fn sqrt(x: f32) -> f32 {
+    unsafe { sqrtf(x) }
+}
+
+pub(crate) fn pow(x: f32, y: f32) -> f32 {
+    unsafe { powf(x, y) }
+}
+
+pub(crate) fn round(x: f32) -> f32 {
+    unsafe { roundf(x) }
+}
@@ -0,0 +1,87 @@
+use crate::{bitmap::Bitmap, geometry::IntRect, gfx::Color, Playdate};
+use alloc::boxed::Box;
+use core::ptr::{addr_of_mut, null_mut};
+
+pub const MENU_IMAGE_WIDTH: i32 = 400;
+pub const MENU_IMAGE_HEIGHT: i32 = 240;
+
+// The system menu covers the right half of the screen, so only 200 pixels of the image
+// are ever visible at once
+pub const MENU_IMAGE_VISIBLE_WIDTH: i32 = 200;
+
+type Render = Box<dyn FnMut(IntRect)>;
+
+struct MenuImage {
+    bitmap: Option<Bitmap>,
+    x_offset: i32,
+    render: Option<Render>,
+    generation: u32,
+}
+
+// The pause event arrives through the event handler rather than the update callback, so
+// the renderer has to be reachable without an app instance
+static mut MENU_IMAGE: MenuImage = MenuImage {
+    bitmap: None,
+    x_offset: 0,
+    render: None,
+    generation: 0,
+};
+
+fn menu_image() -> &'static mut MenuImage {
+    unsafe { &mut *addr_of_mut!(MENU_IMAGE) }
+}
+
+pub(crate) fn set_renderer<T, C>(x_offset: i32, mut render: C)
+where
+    T: 'static,
+    C: FnMut(&mut Playdate<T>, IntRect) + 'static,
+{
+    let state = menu_image();
+    state.x_offset = x_offset.clamp(0, MENU_IMAGE_VISIBLE_WIDTH);
+    state.generation = state.generation.wrapping_add(1);
+    state.render = Some(Box::new(move |visible| {
+        let mut pd = unsafe { Playdate::<T>::init() };
+        let bitmap = menu_image()
+            .bitmap
+            .take()
+            .unwrap_or_else(|| Bitmap::new(MENU_IMAGE_WIDTH, MENU_IMAGE_HEIGHT, Color::White));
+
+        let gfx = pd.graphics_mut();
+        gfx.push_context(Some(bitmap));
+        gfx.clear(Color::White);
+        render(&mut pd, visible);
+        menu_image().bitmap = pd.graphics_mut().pop_context();
+    }));
+}
+
+pub(crate) fn clear_renderer() {
+    let state = menu_image();
+    state.render = None;
+    state.generation = state.generation.wrapping_add(1);
+    invoke_unsafe!(system.setMenuImage, null_mut(), 0);
+    state.bitmap = None;
+}
+
+pub(crate) fn render() {
+    // Take the renderer out while it runs so that it can replace or clear itself
+    let Some(mut render) = menu_image().render.take() else {
+        return;
+    };
+
+    let generation = menu_image().generation;
+    let x_offset = menu_image().x_offset;
+    let visible = IntRect::new(x_offset, 0, MENU_IMAGE_VISIBLE_WIDTH, MENU_IMAGE_HEIGHT);
+
+    render(visible);
+
+    let state = menu_image();
+    if state.generation != generation {
+        return;
+    }
+
+    if let Some(bitmap) = &state.bitmap {
+        invoke_unsafe!(system.setMenuImage, bitmap.as_mut_ptr(), x_offset);
+    }
+
+    state.render = Some(render);
+}
@@ -0,0 +1,167 @@
+use crate::{
+    bitmap::{Bitmap, BitmapFlip},
+    error::{Error, Result},
+    geometry::{IntPoint, IntRect, IntSize},
+    gfx::{Color, Graphics},
+    sprite::DrawMode,
+};
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SliceMode {
+    #[default]
+    Tile,
+    Stretch,
+}
+
+// Draws a bitmap at any size by keeping its corners as they are and filling the edges and
+// center from the parts of the bitmap between them
+pub struct NineSlice {
+    // Row by row from the top left, with `None` for empty slices
+    slices: [Option<Bitmap>; 9],
+    widths: [i32; 3],
+    heights: [i32; 3],
+    edges: SliceMode,
+    center: SliceMode,
+    cache: Option<Bitmap>,
+}
+
+impl NineSlice {
+    // `inner` is the part of `bitmap` that becomes the center, and everything around it is
+    // the corners and edges
+    pub fn new(bitmap: &Bitmap, inner: IntRect) -> Result<Self> {
+        let data = bitmap.data();
+        let bounds = IntRect::new(0, 0, data.width, data.height);
+
+        if inner.width < 0 || inner.height < 0 || !bounds.contains_rect(&inner) {
+            return Err(Error::new(
+                "nine slice inner rect must be inside the bitmap",
+            ));
+        }
+
+        let xs = [0, inner.x, inner.max_x(), bounds.width];
+        let ys = [0, inner.y, inner.max_y(), bounds.height];
+        let widths = [xs[1] - xs[0], xs[2] - xs[1], xs[3] - xs[2]];
+        let heights = [ys[1] - ys[0], ys[2] - ys[1], ys[3] - ys[2]];
+
+        let slices = core::array::from_fn(|i| {
+            let (column, row) = (i % 3, i / 3);
+            let rect = IntRect::new(xs[column], ys[row], widths[column], heights[row]);
+            if rect.is_empty() {
+                return None;
+            }
+
+            // Clear bitmaps are created with a mask, so transparency is kept
+            let mut slice = Bitmap::new(rect.width, rect.height, Color::Clear);
+            slice.blit(bitmap, rect, IntPoint::new(0, 0), DrawMode::Copy);
+            Some(slice)
+        });
+
+        Ok(Self {
+            slices,
+            widths,
+            heights,
+            edges: SliceMode::Tile,
+            center: SliceMode::Tile,
+            cache: None,
+        })
+    }
+
+    pub fn edges(mut self, mode: SliceMode) -> Self {
+        self.edges = mode;
+        self
+    }
+
+    pub fn center(mut self, mode: SliceMode) -> Self {
+        self.center = mode;
+        self
+    }
+
+    // The smallest size that fits the corners
+    pub fn min_size(&self) -> IntSize {
+        IntSize::new(
+            self.widths[0] + self.widths[2],
+            self.heights[0] + self.heights[2],
+        )
+    }
+
+    pub fn draw(&self, gfx: &mut Graphics, rect: IntRect) {
+        let min_size = self.min_size();
+        let center_width = (rect.width - min_size.width).max(0);
+        let center_height = (rect.height - min_size.height).max(0);
+        let widths = [self.widths[0], center_width, self.widths[2]];
+        let heights = [self.heights[0], center_height, self.heights[2]];
+        let xs = [rect.x, rect.x + widths[0], rect.max_x() - widths[2]];
+        let ys = [rect.y, rect.y + heights[0], rect.max_y() - heights[2]];
+
+        for (i, slice) in self.slices.iter().enumerate() {
+            let Some(slice) = slice else {
+                continue;
+            };
+
+            let (column, row) = (i % 3, i / 3);
+            let (x, y) = (xs[column], ys[row]);
+            let (width, height) = (widths[column], heights[row]);
+            if width == 0 || height == 0 {
+                continue;
+            }
+
+            let mode = match (column, row) {
+                (1, 1) => self.center,
+                (1, _) | (_, 1) => self.edges,
+                _ => {
+                    slice.draw(x, y, BitmapFlip::Unflipped);
+                    continue;
+                }
+            };
+
+            match mode {
+                SliceMode::Tile => {
+                    gfx.tile_bitmap(slice, x, y, width, height, BitmapFlip::Unflipped)
+                }
+                SliceMode::Stretch => slice.draw_scaled(
+                    x,
+                    y,
+                    width as f32 / self.widths[column] as f32,
+                    height as f32 / self.heights[row] as f32,
+                ),
+            }
+        }
+    }
+
+    // Draws the slices into a new bitmap, which is transparent wherever the source was
+    pub fn render(&self, gfx: &mut Graphics, size: IntSize) -> Bitmap {
+        let mut bitmap = Bitmap::new(size.width, size.height, Color::Clear);
+
+        gfx.with_context(&mut bitmap, |gfx| {
+            gfx.set_stencil(None);
+            gfx.set_draw_offset(0, 0);
+            gfx.set_draw_mode(DrawMode::Copy);
+            gfx.clear_clip_rect();
+            self.draw(gfx, IntRect::from_origin_size(IntPoint::new(0, 0), size));
+        });
+
+        bitmap
+    }
+
+    // Like `draw`, but keeps the rendering around and only redraws it when the size changes.
+    // Meant for panels that stay the same size from frame to frame.
+    pub fn draw_cached(&mut self, gfx: &mut Graphics, rect: IntRect) {
+        let size = rect.size();
+        let cached = self.cache.as_ref().is_some_and(|bitmap| {
+            let data = bitmap.data();
+            data.width == size.width && data.height == size.height
+        });
+
+        if !cached {
+            self.cache = Some(self.render(gfx, size));
+        }
+
+        if let Some(bitmap) = &self.cache {
+            bitmap.draw(rect.x, rect.y, BitmapFlip::Unflipped);
+        }
+    }
+
+    pub fn clear_cache(&mut self) {
+        self.cache = None;
+    }
+}
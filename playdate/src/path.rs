@@ -0,0 +1,380 @@
+use crate::{
+    geometry::{IntPoint, Point, Rect},
+    gfx::{Color, FillRule, Graphics, LineCapStyle},
+    math::{cos, sin},
+    transform::AffineTransform,
+};
+use alloc::vec::Vec;
+use core::f32::consts::PI;
+
+// Curves are flattened into segments roughly this many pixels long
+const SEGMENT_LENGTH: f32 = 4.0;
+const MAX_SEGMENTS: usize = 64;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Stroke {
+    pub width: i32,
+    pub cap: LineCapStyle,
+}
+
+impl Stroke {
+    pub fn new(width: i32) -> Self {
+        Self {
+            width,
+            cap: LineCapStyle::Butt,
+        }
+    }
+
+    pub fn cap(mut self, cap: LineCapStyle) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    fn draw(&self, gfx: &mut Graphics, points: &[Point], closed: bool, color: Color) {
+        let mut points: Vec<_> = points.iter().map(Point::round).collect();
+        if closed && points.len() > 2 {
+            points.push(points[0]);
+        }
+
+        let mut gfx = gfx.scope();
+        gfx.set_line_cap_style(self.cap);
+        gfx.draw_polyline(&points, self.width, color);
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Polygon {
+    points: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new(points: Vec<Point>) -> Self {
+        Self { points }
+    }
+
+    pub fn from_rect(rect: Rect) -> Self {
+        Self::new(Vec::from([
+            Point::new(rect.min_x(), rect.min_y()),
+            Point::new(rect.max_x(), rect.min_y()),
+            Point::new(rect.max_x(), rect.max_y()),
+            Point::new(rect.min_x(), rect.max_y()),
+        ]))
+    }
+
+    // A polygon with `sides` corners evenly spaced around a circle, the first one at the top
+    pub fn regular(center: Point, radius: f32, sides: usize) -> Self {
+        let step = 360.0 / sides as f32;
+        let points = (0..sides)
+            .map(|i| arc_point(center, radius, i as f32 * step))
+            .collect();
+
+        Self::new(points)
+    }
+
+    pub fn push(&mut self, point: Point) {
+        self.points.push(point);
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn bounds(&self) -> Rect {
+        bounds(&self.points)
+    }
+
+    pub fn transformed(&self, transform: &AffineTransform) -> Self {
+        Self::new(transform.transform_points(&self.points))
+    }
+
+    pub fn contains(&self, point: Point, fill_rule: FillRule) -> bool {
+        let mut winding = 0;
+        let mut crossings = 0;
+
+        for (i, &a) in self.points.iter().enumerate() {
+            let b = self.points[(i + 1) % self.points.len()];
+            if (a.y <= point.y) == (b.y <= point.y) {
+                continue;
+            }
+
+            let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if x > point.x {
+                crossings += 1;
+                winding += if b.y > a.y { 1 } else { -1 };
+            }
+        }
+
+        match fill_rule {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => crossings % 2 == 1,
+        }
+    }
+
+    pub fn fill(&self, gfx: &mut Graphics, color: Color, fill_rule: FillRule) {
+        let points: Vec<IntPoint> = self.points.iter().map(Point::round).collect();
+        gfx.fill_polygon(&points, color, fill_rule);
+    }
+
+    pub fn stroke(&self, gfx: &mut Graphics, stroke: &Stroke, color: Color) {
+        stroke.draw(gfx, &self.points, true, color);
+    }
+}
+
+impl From<Vec<Point>> for Polygon {
+    fn from(points: Vec<Point>) -> Self {
+        Self::new(points)
+    }
+}
+
+impl From<&[Point]> for Polygon {
+    fn from(points: &[Point]) -> Self {
+        Self::new(points.into())
+    }
+}
+
+// A single contour of lines and curves. Curves are flattened into line segments as they
+// are added, so the path can be stroked as a polyline or filled as a polygon.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    points: Vec<Point>,
+    closed: bool,
+}
+
+impl Path {
+    pub fn new(start: Point) -> Self {
+        Self {
+            points: Vec::from([start]),
+            closed: false,
+        }
+    }
+
+    // A path along the arc of a circle. Angles are in degrees, clockwise from the top,
+    // like `Graphics::draw_ellipse`.
+    pub fn arc(center: Point, radius: f32, start_angle: f32, end_angle: f32) -> Self {
+        Self::new(arc_point(center, radius, start_angle)).arc_to(
+            center,
+            radius,
+            start_angle,
+            end_angle,
+        )
+    }
+
+    pub fn current_point(&self) -> Point {
+        *self.points.last().unwrap()
+    }
+
+    pub fn line_to(mut self, point: Point) -> Self {
+        self.points.push(point);
+        self
+    }
+
+    pub fn quad_to(mut self, control: Point, end: Point) -> Self {
+        let start = self.current_point();
+        let length = start.distance(control) + control.distance(end);
+
+        for t in steps(length) {
+            let u = 1.0 - t;
+            self.points
+                .push(start * (u * u) + control * (2.0 * u * t) + end * (t * t));
+        }
+
+        self
+    }
+
+    pub fn cubic_to(mut self, control1: Point, control2: Point, end: Point) -> Self {
+        let start = self.current_point();
+        let length =
+            start.distance(control1) + control1.distance(control2) + control2.distance(end);
+
+        for t in steps(length) {
+            let u = 1.0 - t;
+            self.points.push(
+                start * (u * u * u)
+                    + control1 * (3.0 * u * u * t)
+                    + control2 * (3.0 * u * t * t)
+                    + end * (t * t * t),
+            );
+        }
+
+        self
+    }
+
+    // Continues the path with a line to the start of the arc and then along it
+    pub fn arc_to(mut self, center: Point, radius: f32, start_angle: f32, end_angle: f32) -> Self {
+        let start = arc_point(center, radius, start_angle);
+        if self.current_point() != start {
+            self.points.push(start);
+        }
+
+        let sweep = end_angle - start_angle;
+        let length = (sweep * PI / 180.0 * radius).abs();
+
+        for t in steps(length) {
+            let angle = start_angle + sweep * t;
+            self.points.push(arc_point(center, radius, angle));
+        }
+
+        self
+    }
+
+    pub fn close(mut self) -> Self {
+        self.closed = true;
+        self
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    pub fn bounds(&self) -> Rect {
+        bounds(&self.points)
+    }
+
+    pub fn transformed(&self, transform: &AffineTransform) -> Self {
+        Self {
+            points: transform.transform_points(&self.points),
+            closed: self.closed,
+        }
+    }
+
+    pub fn to_polygon(&self) -> Polygon {
+        Polygon::new(self.points.clone())
+    }
+
+    pub fn into_polygon(self) -> Polygon {
+        Polygon::new(self.points)
+    }
+
+    // Fills the area enclosed by the path, whether or not it was closed
+    pub fn fill(&self, gfx: &mut Graphics, color: Color, fill_rule: FillRule) {
+        let points: Vec<IntPoint> = self.points.iter().map(Point::round).collect();
+        gfx.fill_polygon(&points, color, fill_rule);
+    }
+
+    pub fn stroke(&self, gfx: &mut Graphics, stroke: &Stroke, color: Color) {
+        stroke.draw(gfx, &self.points, self.closed, color);
+    }
+}
+
+fn arc_point(center: Point, radius: f32, degrees: f32) -> Point {
+    let radians = degrees * PI / 180.0;
+    Point::new(
+        center.x + radius * sin(radians),
+        center.y - radius * cos(radians),
+    )
+}
+
+// The parameters after 0 at which a curve of roughly `length` pixels is sampled, ending
+// at exactly 1
+fn steps(length: f32) -> impl Iterator<Item = f32> {
+    let count = ((length / SEGMENT_LENGTH) as usize).clamp(1, MAX_SEGMENTS);
+    (1..=count).map(move |i| i as f32 / count as f32)
+}
+
+fn bounds(points: &[Point]) -> Rect {
+    let Some(&first) = points.first() else {
+        return Rect::default();
+    };
+
+    let (mut min, mut max) = (first, first);
+    for point in points {
+        min = Point::new(min.x.min(point.x), min.y.min(point.y));
+        max = Point::new(max.x.max(point.x), max.y.max(point.y));
+    }
+
+    Rect::from_points(min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Point, b: Point) -> bool {
+        (a.x - b.x).abs() < 1e-3 && (a.y - b.y).abs() < 1e-3
+    }
+
+    #[test]
+    fn polygon_contains() {
+        let square = Polygon::from_rect(Rect::new(0.0, 0.0, 10.0, 10.0));
+        assert!(square.contains(Point::new(5.0, 5.0), FillRule::EvenOdd));
+        assert!(!square.contains(Point::new(15.0, 5.0), FillRule::NonZero));
+        assert!(!Polygon::default().contains(Point::new(0.0, 0.0), FillRule::NonZero));
+
+        // A pentagram's center is inside for non-zero but outside for even-odd
+        let pentagon = Polygon::regular(Point::new(0.0, 0.0), 10.0, 5);
+        let points = pentagon.points();
+        let star = Polygon::new((0..5).map(|i| points[i * 2 % 5]).collect());
+        assert!(star.contains(Point::new(0.0, 0.0), FillRule::NonZero));
+        assert!(!star.contains(Point::new(0.0, 0.0), FillRule::EvenOdd));
+    }
+
+    #[test]
+    fn regular_polygons_start_at_the_top() {
+        let polygon = Polygon::regular(Point::new(0.0, 0.0), 10.0, 4);
+        assert_eq!(polygon.len(), 4);
+        assert!(close(polygon.points()[0], Point::new(0.0, -10.0)));
+        assert!(close(polygon.points()[1], Point::new(10.0, 0.0)));
+    }
+
+    #[test]
+    fn flattens_curves() {
+        let path = Path::new(Point::new(0.0, 0.0))
+            .quad_to(Point::new(50.0, 100.0), Point::new(100.0, 0.0));
+        assert!(path.points().len() > 10 && path.points().len() <= MAX_SEGMENTS + 1);
+        assert_eq!(path.current_point(), Point::new(100.0, 0.0));
+        assert!((path.bounds().max_y() - 50.0).abs() < 0.1);
+
+        let path = Path::new(Point::new(0.0, 0.0)).cubic_to(
+            Point::new(0.0, 10.0),
+            Point::new(10.0, 10.0),
+            Point::new(10.0, 0.0),
+        );
+        assert_eq!(path.current_point(), Point::new(10.0, 0.0));
+        assert!(path.bounds().max_y() <= 10.0);
+    }
+
+    #[test]
+    fn arcs() {
+        let path = Path::arc(Point::new(0.0, 0.0), 10.0, 0.0, 90.0).close();
+        assert!(path.is_closed());
+        assert!(close(path.points()[0], Point::new(0.0, -10.0)));
+        assert!(close(path.current_point(), Point::new(10.0, 0.0)));
+
+        let bounds = path.bounds();
+        assert!(close(bounds.origin(), Point::new(0.0, -10.0)));
+        assert!(close(
+            Point::new(bounds.max_x(), bounds.max_y()),
+            Point::new(10.0, 0.0)
+        ));
+
+        // Continuing from elsewhere adds a line to the start of the arc
+        let path = Path::new(Point::new(0.0, 0.0)).arc_to(Point::new(0.0, 0.0), 5.0, 0.0, 10.0);
+        assert!(close(path.points()[1], Point::new(0.0, -5.0)));
+    }
+
+    #[test]
+    fn transforms() {
+        let path = Path::new(Point::new(1.0, 1.0))
+            .line_to(Point::new(2.0, 3.0))
+            .close();
+        let moved = path.transformed(&AffineTransform::translation(1.0, -1.0));
+        assert_eq!(
+            moved.points(),
+            &[Point::new(2.0, 0.0), Point::new(3.0, 2.0)]
+        );
+        assert!(moved.is_closed());
+        assert_eq!(moved.into_polygon().bounds(), Rect::new(2.0, 0.0, 1.0, 2.0));
+    }
+}
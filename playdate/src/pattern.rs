@@ -0,0 +1,146 @@
+use crate::bitmap::Bitmap;
+use playdate_sys::{LCDColor, LCDPattern};
+
+// Ordered dither thresholds. The 4x4 and 2x2 matrices are the top left corner of this one
+// shifted right by 2 and 4 bits respectively.
+pub(crate) const BAYER_8X8: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+// An 8x8 pattern in the same layout as `LCDPattern`: eight rows of pixels followed by
+// eight rows of mask, most significant bit first. Set pixel bits are white and set mask
+// bits are opaque.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Pattern(LCDPattern);
+
+impl Pattern {
+    pub const BLACK: Pattern = Pattern::new([0x00; 8]);
+    pub const WHITE: Pattern = Pattern::new([0xff; 8]);
+    pub const CHECKERBOARD: Pattern =
+        Pattern::new([0xaa, 0x55, 0xaa, 0x55, 0xaa, 0x55, 0xaa, 0x55]);
+
+    pub const fn new(rows: [u8; 8]) -> Self {
+        Self::with_mask(rows, [0xff; 8])
+    }
+
+    pub const fn with_mask(rows: [u8; 8], mask: [u8; 8]) -> Self {
+        let mut pattern = [0; 16];
+        let mut i = 0;
+
+        while i < 8 {
+            pattern[i] = rows[i];
+            pattern[i + 8] = mask[i];
+            i += 1;
+        }
+
+        Self(pattern)
+    }
+
+    pub fn from_bitmap(bitmap: &Bitmap, x: i32, y: i32) -> Self {
+        let mut color: LCDColor = 0;
+        invoke_unsafe!(
+            graphics.setColorToPattern,
+            &mut color,
+            bitmap.as_mut_ptr(),
+            x,
+            y
+        );
+
+        let pattern = unsafe { *(color as *const LCDPattern) };
+        Self(pattern)
+    }
+
+    // Levels run from 0 (black) to 4 (white)
+    pub fn bayer_2x2(level: u8) -> Self {
+        Self::bayer(level.min(4), 4)
+    }
+
+    // Levels run from 0 (black) to 16 (white)
+    pub fn bayer_4x4(level: u8) -> Self {
+        Self::bayer(level.min(16), 2)
+    }
+
+    // Levels run from 0 (black) to 64 (white)
+    pub fn bayer_8x8(level: u8) -> Self {
+        Self::bayer(level.min(64), 0)
+    }
+
+    // Picks the closest 8x8 Bayer level for a brightness between 0.0 (black) and 1.0 (white)
+    pub fn gray(brightness: f32) -> Self {
+        let level = crate::math::round(brightness.clamp(0.0, 1.0) * 64.0) as u8;
+        Self::bayer_8x8(level)
+    }
+
+    fn bayer(level: u8, shift: u32) -> Self {
+        let mut rows = [0; 8];
+
+        for (row, thresholds) in rows.iter_mut().zip(BAYER_8X8) {
+            for (x, threshold) in thresholds.into_iter().enumerate() {
+                if threshold >> shift < level {
+                    *row |= 0x80 >> x;
+                }
+            }
+        }
+
+        Self::new(rows)
+    }
+
+    // Black stripes running from the top left to the bottom right, `thickness` pixels wide
+    // out of every eight
+    pub fn diagonal(thickness: u8) -> Self {
+        let mut rows = [0xff; 8];
+
+        for (y, row) in rows.iter_mut().enumerate() {
+            for x in 0..8 {
+                if (x + 8 - y) % 8 < thickness as usize {
+                    *row &= !(0x80 >> x);
+                }
+            }
+        }
+
+        Self::new(rows)
+    }
+
+    pub fn rows(&self) -> [u8; 8] {
+        let mut rows = [0; 8];
+        rows.copy_from_slice(&self.0[..8]);
+        rows
+    }
+
+    pub fn mask(&self) -> [u8; 8] {
+        let mut mask = [0; 8];
+        mask.copy_from_slice(&self.0[8..]);
+        mask
+    }
+
+    pub fn inverted(&self) -> Self {
+        let rows = self.rows().map(|row| !row);
+        Self::with_mask(rows, self.mask())
+    }
+
+    pub fn flipped(&self) -> Self {
+        let rows = self.rows().map(|row| row.reverse_bits());
+        let mask = self.mask().map(|row| row.reverse_bits());
+        Self::with_mask(rows, mask)
+    }
+
+    pub fn is_white(&self, x: usize, y: usize) -> bool {
+        self.0[y % 8] & (0x80 >> (x % 8)) != 0
+    }
+
+    pub fn is_opaque(&self, x: usize, y: usize) -> bool {
+        self.0[8 + y % 8] & (0x80 >> (x % 8)) != 0
+    }
+
+    pub(crate) fn as_lcd_color(&self) -> LCDColor {
+        self.0.as_ptr() as LCDColor
+    }
+}
@@ -0,0 +1,379 @@
+use crate::gfx::SolidColor;
+use core::slice::{ChunksExact, ChunksExactMut};
+
+// Bitmap data packs 8 pixels per byte, most significant bit first, and pads each row out
+// to `row_bytes`. Set bits are white in the pixel data and opaque in the mask.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Layout {
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+    pub(crate) row_bytes: usize,
+}
+
+impl Layout {
+    pub(crate) fn len(&self) -> usize {
+        self.row_bytes * self.height as usize
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<(usize, u8)> {
+        if !(0..self.width).contains(&x) || !(0..self.height).contains(&y) {
+            return None;
+        }
+
+        let index = y as usize * self.row_bytes + x as usize / 8;
+        Some((index, 0x80 >> (x % 8)))
+    }
+
+    fn get(&self, data: &[u8], x: i32, y: i32) -> Option<bool> {
+        let (index, bit) = self.index(x, y)?;
+        Some(data[index] & bit != 0)
+    }
+
+    fn set(&self, data: &mut [u8], x: i32, y: i32, value: bool) -> bool {
+        let Some((index, bit)) = self.index(x, y) else {
+            return false;
+        };
+
+        if value {
+            data[index] |= bit;
+        } else {
+            data[index] &= !bit;
+        }
+
+        true
+    }
+
+    fn row<'a>(&self, data: &'a [u8], y: i32) -> Option<&'a [u8]> {
+        if !(0..self.height).contains(&y) {
+            return None;
+        }
+
+        let start = y as usize * self.row_bytes;
+        Some(&data[start..start + self.row_bytes])
+    }
+
+    fn row_mut<'a>(&self, data: &'a mut [u8], y: i32) -> Option<&'a mut [u8]> {
+        if !(0..self.height).contains(&y) {
+            return None;
+        }
+
+        let start = y as usize * self.row_bytes;
+        Some(&mut data[start..start + self.row_bytes])
+    }
+
+    // Zero width bitmaps have no bytes per row, which chunks_exact doesn't accept, and no rows
+    // to iterate over
+    fn rows<'a>(&self, data: &'a [u8]) -> ChunksExact<'a, u8> {
+        let len = if self.row_bytes > 0 { data.len() } else { 0 };
+        data[..len].chunks_exact(self.row_bytes.max(1))
+    }
+
+    fn rows_mut<'a>(&self, data: &'a mut [u8]) -> ChunksExactMut<'a, u8> {
+        let len = if self.row_bytes > 0 { data.len() } else { 0 };
+        data[..len].chunks_exact_mut(self.row_bytes.max(1))
+    }
+}
+
+fn color(white: bool) -> SolidColor {
+    if white {
+        SolidColor::White
+    } else {
+        SolidColor::Black
+    }
+}
+
+pub struct Pixels<'a> {
+    layout: Layout,
+    data: &'a [u8],
+    mask: Option<&'a [u8]>,
+}
+
+impl<'a> Pixels<'a> {
+    pub(crate) fn new(layout: Layout, data: &'a [u8], mask: Option<&'a [u8]>) -> Self {
+        Self { layout, data, mask }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.layout.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.layout.height
+    }
+
+    pub fn row_bytes(&self) -> usize {
+        self.layout.row_bytes
+    }
+
+    // Pixels hidden by the mask are `Clear`
+    pub fn get(&self, x: i32, y: i32) -> Option<SolidColor> {
+        let white = self.layout.get(self.data, x, y)?;
+        match self.mask().map(|mask| mask.get(x, y)) {
+            Some(Some(false)) => Some(SolidColor::Clear),
+            _ => Some(color(white)),
+        }
+    }
+
+    // Whether the pixel data is white, ignoring the mask
+    pub(crate) fn white(&self, x: i32, y: i32) -> Option<bool> {
+        self.layout.get(self.data, x, y)
+    }
+
+    pub fn row(&self, y: i32) -> Option<&'a [u8]> {
+        self.layout.row(self.data, y)
+    }
+
+    pub fn rows(&self) -> ChunksExact<'a, u8> {
+        self.layout.rows(self.data)
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn has_mask(&self) -> bool {
+        self.mask.is_some()
+    }
+
+    pub fn mask(&self) -> Option<Mask<'a>> {
+        let layout = self.layout;
+        self.mask.map(|data| Mask { layout, data })
+    }
+}
+
+pub struct PixelsMut<'a> {
+    layout: Layout,
+    data: &'a mut [u8],
+    mask: Option<&'a mut [u8]>,
+}
+
+impl<'a> PixelsMut<'a> {
+    pub(crate) fn new(layout: Layout, data: &'a mut [u8], mask: Option<&'a mut [u8]>) -> Self {
+        Self { layout, data, mask }
+    }
+
+    pub fn as_pixels(&self) -> Pixels<'_> {
+        Pixels::new(self.layout, self.data, self.mask.as_deref())
+    }
+
+    pub fn width(&self) -> i32 {
+        self.layout.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.layout.height
+    }
+
+    pub fn row_bytes(&self) -> usize {
+        self.layout.row_bytes
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<SolidColor> {
+        self.as_pixels().get(x, y)
+    }
+
+    // Black and white also make the pixel opaque if there's a mask. Clear hides it with
+    // the mask, and does nothing on bitmaps without one. Returns false when out of bounds.
+    pub fn set(&mut self, x: i32, y: i32, color: SolidColor) -> bool {
+        let Some(white) = self.layout.get(self.data, x, y) else {
+            return false;
+        };
+
+        let (white, opaque) = match color {
+            SolidColor::Black => (false, true),
+            SolidColor::White => (true, true),
+            SolidColor::Xor => (!white, true),
+            SolidColor::Clear => (white, false),
+        };
+
+        self.layout.set(self.data, x, y, white);
+        if let Some(mask) = self.mask.as_deref_mut() {
+            self.layout.set(mask, x, y, opaque);
+        }
+
+        true
+    }
+
+    // Sets the pixel data, leaving the mask alone
+    pub(crate) fn set_white(&mut self, x: i32, y: i32, white: bool) -> bool {
+        self.layout.set(self.data, x, y, white)
+    }
+
+    pub fn fill(&mut self, color: SolidColor) {
+        match color {
+            SolidColor::Black => self.data.fill(0),
+            SolidColor::White => self.data.fill(0xff),
+            SolidColor::Xor => self.data.iter_mut().for_each(|byte| *byte = !*byte),
+            SolidColor::Clear => {}
+        }
+
+        if let Some(mask) = self.mask.as_deref_mut() {
+            mask.fill(if color == SolidColor::Clear { 0 } else { 0xff });
+        }
+    }
+
+    pub fn row(&self, y: i32) -> Option<&[u8]> {
+        self.layout.row(self.data, y)
+    }
+
+    pub fn row_mut(&mut self, y: i32) -> Option<&mut [u8]> {
+        self.layout.row_mut(self.data, y)
+    }
+
+    pub fn rows(&self) -> ChunksExact<'_, u8> {
+        self.layout.rows(self.data)
+    }
+
+    pub fn rows_mut(&mut self) -> ChunksExactMut<'_, u8> {
+        self.layout.rows_mut(self.data)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data
+    }
+
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.data
+    }
+
+    pub fn has_mask(&self) -> bool {
+        self.mask.is_some()
+    }
+
+    pub fn mask(&self) -> Option<Mask<'_>> {
+        let layout = self.layout;
+        self.mask.as_deref().map(|data| Mask { layout, data })
+    }
+
+    pub fn mask_mut(&mut self) -> Option<MaskMut<'_>> {
+        let layout = self.layout;
+        self.mask
+            .as_deref_mut()
+            .map(|data| MaskMut { layout, data })
+    }
+}
+
+pub struct Mask<'a> {
+    layout: Layout,
+    data: &'a [u8],
+}
+
+impl<'a> Mask<'a> {
+    // True where the bitmap is opaque
+    pub fn get(&self, x: i32, y: i32) -> Option<bool> {
+        self.layout.get(self.data, x, y)
+    }
+
+    pub fn row(&self, y: i32) -> Option<&'a [u8]> {
+        self.layout.row(self.data, y)
+    }
+
+    pub fn rows(&self) -> ChunksExact<'a, u8> {
+        self.layout.rows(self.data)
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+pub struct MaskMut<'a> {
+    layout: Layout,
+    data: &'a mut [u8],
+}
+
+impl MaskMut<'_> {
+    pub fn get(&self, x: i32, y: i32) -> Option<bool> {
+        self.layout.get(self.data, x, y)
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, opaque: bool) -> bool {
+        self.layout.set(self.data, x, y, opaque)
+    }
+
+    pub fn fill(&mut self, opaque: bool) {
+        self.data.fill(if opaque { 0xff } else { 0 });
+    }
+
+    pub fn row(&self, y: i32) -> Option<&[u8]> {
+        self.layout.row(self.data, y)
+    }
+
+    pub fn row_mut(&mut self, y: i32) -> Option<&mut [u8]> {
+        self.layout.row_mut(self.data, y)
+    }
+
+    pub fn rows(&self) -> ChunksExact<'_, u8> {
+        self.layout.rows(self.data)
+    }
+
+    pub fn rows_mut(&mut self) -> ChunksExactMut<'_, u8> {
+        self.layout.rows_mut(self.data)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data
+    }
+
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUT: Layout = Layout {
+        width: 10,
+        height: 2,
+        row_bytes: 2,
+    };
+
+    #[test]
+    fn reads_pixels_and_rows() {
+        let data = [0x80, 0x40, 0x00, 0xff];
+        let mask = [0xff, 0xff, 0x7f, 0xff];
+        let pixels = Pixels::new(LAYOUT, &data, Some(&mask));
+
+        assert_eq!(pixels.get(0, 0), Some(SolidColor::White));
+        assert_eq!(pixels.get(1, 0), Some(SolidColor::Black));
+        assert_eq!(pixels.get(9, 0), Some(SolidColor::White));
+        assert_eq!(pixels.get(0, 1), Some(SolidColor::Clear));
+        assert_eq!(pixels.get(10, 0), None);
+        assert_eq!(pixels.get(0, -1), None);
+
+        assert_eq!(pixels.row(1), Some(&data[2..]));
+        assert_eq!(pixels.row(2), None);
+        assert_eq!(pixels.rows().count(), 2);
+    }
+
+    #[test]
+    fn sets_pixels_and_mask() {
+        let (mut data, mut mask) = ([0u8; 4], [0xffu8; 4]);
+        let mut pixels = PixelsMut::new(LAYOUT, &mut data, Some(&mut mask));
+
+        assert!(pixels.set(2, 1, SolidColor::White));
+        assert!(pixels.set(3, 1, SolidColor::Clear));
+        assert!(!pixels.set(10, 1, SolidColor::White));
+        assert_eq!(data, [0, 0, 0x20, 0]);
+        assert_eq!(mask, [0xff, 0xff, 0xef, 0xff]);
+    }
+
+    #[test]
+    fn zero_width_has_no_rows() {
+        let layout = Layout {
+            width: 0,
+            height: 3,
+            row_bytes: 0,
+        };
+
+        let pixels = Pixels::new(layout, &[], None);
+        assert_eq!(pixels.rows().count(), 0);
+        assert_eq!(pixels.row(0), Some(&[][..]));
+
+        let mut data = [];
+        let mut pixels = PixelsMut::new(layout, &mut data, None);
+        assert_eq!(pixels.rows_mut().count(), 0);
+    }
+}
@@ -0,0 +1,427 @@
+use crate::{
+    error::{Error, Result},
+    gfx::SolidColor,
+    image::{luminance, Image},
+    pixels::Pixels,
+    zlib,
+};
+use alloc::{format, vec, vec::Vec};
+
+pub(crate) const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+// Adam7 passes as (x start, y start, x step, y step)
+const ADAM7: [(usize, usize, usize, usize); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+fn error(message: &str) -> Error {
+    Error::new(format!("invalid PNG: {}", message))
+}
+
+struct Header {
+    width: usize,
+    height: usize,
+    depth: u8,
+    color_type: u8,
+    interlaced: bool,
+}
+
+impl Header {
+    fn channels(&self) -> usize {
+        match self.color_type {
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => 1,
+        }
+    }
+
+    fn bits_per_pixel(&self) -> usize {
+        self.channels() * self.depth as usize
+    }
+
+    fn row_bytes(&self, width: usize) -> usize {
+        (width * self.bits_per_pixel()).div_ceil(8)
+    }
+}
+
+pub(crate) fn decode(data: &[u8]) -> Result<Image> {
+    if !data.starts_with(&SIGNATURE) {
+        return Err(error("missing signature"));
+    }
+
+    let mut rest = &data[SIGNATURE.len()..];
+    let mut header = None;
+    let mut palette: &[u8] = &[];
+    let mut transparency: Option<&[u8]> = None;
+    let mut compressed = Vec::new();
+
+    loop {
+        if rest.len() < 12 {
+            return Err(error("truncated chunk"));
+        }
+
+        let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let kind = &rest[4..8];
+        let body = rest
+            .get(8..8 + len)
+            .ok_or_else(|| error("truncated chunk"))?;
+        rest = rest
+            .get(12 + len..)
+            .ok_or_else(|| error("truncated chunk"))?;
+
+        match kind {
+            b"IHDR" => header = Some(parse_header(body)?),
+            b"PLTE" => palette = body,
+            b"tRNS" => transparency = Some(body),
+            b"IDAT" => compressed.extend_from_slice(body),
+            b"IEND" => break,
+            // Ancillary chunks have a lowercase first letter and can be skipped
+            _ if kind[0].is_ascii_lowercase() => {}
+            _ => return Err(error("unsupported critical chunk")),
+        }
+    }
+
+    let header = header.ok_or_else(|| error("missing IHDR"))?;
+    if header.color_type == 3 && palette.is_empty() {
+        return Err(error("missing palette"));
+    }
+
+    let has_alpha = header.color_type & 4 != 0 || transparency.is_some();
+    let mut image = Image::new(header.width, header.height, has_alpha)?;
+    let passes: &[_] = if header.interlaced {
+        &ADAM7
+    } else {
+        &[(0, 0, 1, 1)]
+    };
+
+    // Each row of each pass is a filter byte followed by the row's pixels
+    let size = passes
+        .iter()
+        .map(|&(x0, y0, dx, dy)| {
+            let width = (header.width + dx - 1 - x0) / dx;
+            let height = (header.height + dy - 1 - y0) / dy;
+            if width == 0 {
+                0
+            } else {
+                height * (1 + header.row_bytes(width))
+            }
+        })
+        .sum();
+    let data = zlib::decompress(&compressed, size)?;
+
+    let bpp = header.bits_per_pixel().div_ceil(8);
+    let mut offset = 0;
+
+    for &(x0, y0, dx, dy) in passes {
+        let width = (header.width + dx - 1 - x0) / dx;
+        let height = (header.height + dy - 1 - y0) / dy;
+        if width == 0 || height == 0 {
+            continue;
+        }
+
+        let row_bytes = header.row_bytes(width);
+        let mut previous = vec![0; row_bytes];
+
+        for row in 0..height {
+            let filter = *data.get(offset).ok_or_else(|| error("truncated data"))?;
+            let mut current = data
+                .get(offset + 1..offset + 1 + row_bytes)
+                .ok_or_else(|| error("truncated data"))?
+                .to_vec();
+            offset += 1 + row_bytes;
+
+            unfilter(filter, &mut current, &previous, bpp)?;
+
+            for column in 0..width {
+                let (grey, alpha) = pixel(&header, &current, column, palette, transparency)?;
+                image.set(x0 + column * dx, y0 + row * dy, grey, alpha);
+            }
+
+            previous = current;
+        }
+    }
+
+    Ok(image)
+}
+
+fn parse_header(body: &[u8]) -> Result<Header> {
+    if body.len() != 13 {
+        return Err(error("bad IHDR"));
+    }
+
+    let width = u32::from_be_bytes([body[0], body[1], body[2], body[3]]) as usize;
+    let height = u32::from_be_bytes([body[4], body[5], body[6], body[7]]) as usize;
+    let (depth, color_type) = (body[8], body[9]);
+
+    let valid_depth = match color_type {
+        0 => matches!(depth, 1 | 2 | 4 | 8 | 16),
+        3 => matches!(depth, 1 | 2 | 4 | 8),
+        2 | 4 | 6 => matches!(depth, 8 | 16),
+        _ => false,
+    };
+
+    if !valid_depth {
+        return Err(error("unsupported bit depth or color type"));
+    }
+
+    if body[10] != 0 || body[11] != 0 || body[12] > 1 {
+        return Err(error("unsupported compression, filter or interlace method"));
+    }
+
+    Ok(Header {
+        width,
+        height,
+        depth,
+        color_type,
+        interlaced: body[12] == 1,
+    })
+}
+
+fn unfilter(filter: u8, row: &mut [u8], previous: &[u8], bpp: usize) -> Result<()> {
+    for i in 0..row.len() {
+        let left = if i >= bpp { row[i - bpp] } else { 0 };
+        let up = previous[i];
+        let up_left = if i >= bpp { previous[i - bpp] } else { 0 };
+
+        let prediction = match filter {
+            0 => 0,
+            1 => left,
+            2 => up,
+            3 => ((left as u16 + up as u16) / 2) as u8,
+            4 => paeth(left, up, up_left),
+            _ => return Err(error("unknown filter type")),
+        };
+
+        row[i] = row[i].wrapping_add(prediction);
+    }
+
+    Ok(())
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+// The `index`th sample of a row at full precision
+fn sample(row: &[u8], index: usize, depth: u8) -> u16 {
+    match depth {
+        16 => u16::from_be_bytes([row[index * 2], row[index * 2 + 1]]),
+        8 => row[index] as u16,
+        _ => {
+            let bit = index * depth as usize;
+            let shift = 8 - depth as usize - bit % 8;
+            ((row[bit / 8] >> shift) & ((1 << depth) - 1)) as u16
+        }
+    }
+}
+
+fn scale(value: u16, depth: u8) -> u8 {
+    match depth {
+        16 => (value >> 8) as u8,
+        8 => value as u8,
+        _ => (value * 255 / ((1 << depth) - 1)) as u8,
+    }
+}
+
+// Returns the greyscale value and alpha of a pixel
+fn pixel(
+    header: &Header,
+    row: &[u8],
+    column: usize,
+    palette: &[u8],
+    transparency: Option<&[u8]>,
+) -> Result<(u8, u8)> {
+    let depth = header.depth;
+    let channels = header.channels();
+    let get = |channel: usize| sample(row, column * channels + channel, depth);
+    let key = |channel: usize| {
+        transparency
+            .and_then(|t| t.get(channel * 2..channel * 2 + 2))
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    };
+
+    let pixel = match header.color_type {
+        0 => {
+            let value = get(0);
+            let alpha = if key(0) == Some(value) { 0 } else { 255 };
+            (scale(value, depth), alpha)
+        }
+        2 => {
+            let (r, g, b) = (get(0), get(1), get(2));
+            let transparent = key(0) == Some(r) && key(1) == Some(g) && key(2) == Some(b);
+            let grey = luminance(scale(r, depth), scale(g, depth), scale(b, depth));
+            (grey, if transparent { 0 } else { 255 })
+        }
+        3 => {
+            let index = get(0) as usize;
+            let rgb = palette
+                .get(index * 3..index * 3 + 3)
+                .ok_or_else(|| error("palette index out of range"))?;
+            let alpha = transparency
+                .and_then(|t| t.get(index))
+                .copied()
+                .unwrap_or(255);
+            (luminance(rgb[0], rgb[1], rgb[2]), alpha)
+        }
+        4 => (scale(get(0), depth), scale(get(1), depth)),
+        _ => {
+            let (r, g, b) = (
+                scale(get(0), depth),
+                scale(get(1), depth),
+                scale(get(2), depth),
+            );
+            (luminance(r, g, b), scale(get(3), depth))
+        }
+    };
+
+    Ok(pixel)
+}
+
+// Bitmaps without a mask are saved as 1 bit greyscale. Masked ones use a 2 bit palette of
+// black, white and transparent.
+pub(crate) fn encode(pixels: &Pixels) -> Vec<u8> {
+    let (width, height) = (pixels.width() as usize, pixels.height() as usize);
+    let masked = pixels.has_mask();
+    let (depth, color_type) = if masked { (2, 3) } else { (1, 0) };
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    header.extend_from_slice(&[depth, color_type, 0, 0, 0]);
+
+    let row_bytes = (width * depth as usize).div_ceil(8);
+    let mut raw = Vec::with_capacity((row_bytes + 1) * height);
+
+    for y in 0..height {
+        // Each row starts with its filter type, which is always none
+        raw.push(0);
+
+        if !masked {
+            raw.extend_from_slice(&pixels.row(y as i32).unwrap()[..row_bytes]);
+            continue;
+        }
+
+        let mut byte = 0;
+        for x in 0..width {
+            let index = match pixels.get(x as i32, y as i32) {
+                Some(SolidColor::White) => 1,
+                Some(SolidColor::Black) => 0,
+                _ => 2,
+            };
+
+            byte |= index << (6 - (x % 4) * 2);
+            if x % 4 == 3 || x == width - 1 {
+                raw.push(byte);
+                byte = 0;
+            }
+        }
+    }
+
+    let mut out = Vec::from(SIGNATURE);
+    write_chunk(&mut out, b"IHDR", &header);
+    if masked {
+        write_chunk(&mut out, b"PLTE", &[0, 0, 0, 255, 255, 255, 0, 0, 0]);
+        write_chunk(&mut out, b"tRNS", &[255, 255, 0]);
+    }
+    write_chunk(&mut out, b"IDAT", &zlib::compress(&raw));
+    write_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], body: &[u8]) {
+    out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(body);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb88320 & mask);
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixels::Layout;
+
+    const LAYOUT: Layout = Layout {
+        width: 3,
+        height: 2,
+        row_bytes: 2,
+    };
+
+    #[test]
+    fn round_trips_unmasked() {
+        let data = [0b1010_0000, 0, 0b0110_0000, 0];
+        let image = decode(&encode(&Pixels::new(LAYOUT, &data, None))).unwrap();
+
+        assert_eq!(image.get(0, 0), (255, None));
+        assert_eq!(image.get(1, 0), (0, None));
+        assert_eq!(image.get(2, 0), (255, None));
+        assert_eq!(image.get(0, 1), (0, None));
+        assert_eq!(image.get(1, 1), (255, None));
+    }
+
+    #[test]
+    fn round_trips_masked() {
+        let data = [0b1000_0000, 0, 0, 0];
+        let mask = [0b1100_0000, 0, 0b1110_0000, 0];
+        let image = decode(&encode(&Pixels::new(LAYOUT, &data, Some(&mask)))).unwrap();
+
+        assert_eq!(image.get(0, 0), (255, Some(255)));
+        assert_eq!(image.get(1, 0), (0, Some(255)));
+        assert_eq!(image.get(2, 0).1, Some(0));
+        assert_eq!(image.get(2, 1), (0, Some(255)));
+    }
+
+    #[test]
+    fn rejects_bad_pngs() {
+        let data = [0b1010_0000, 0, 0b0110_0000, 0];
+        let png = encode(&Pixels::new(LAYOUT, &data, None));
+
+        assert!(decode(&png[1..]).is_err());
+        assert!(decode(&png[..png.len() - 12]).is_err());
+
+        // Claims to be 1x1, so the data inflates to more than expected
+        let mut small = png.clone();
+        small[16..24].copy_from_slice(&[0, 0, 0, 1, 0, 0, 0, 1]);
+        assert!(decode(&small).is_err());
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+    }
+}
@@ -0,0 +1,400 @@
+use crate::{error::Result, font::Font, geometry::IntSize, gfx::Graphics};
+use alloc::vec::Vec;
+use core::ffi::CStr;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FontVariant {
+    #[default]
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic,
+}
+
+impl FontVariant {
+    fn new(bold: bool, italic: bool) -> Self {
+        match (bold, italic) {
+            (false, false) => Self::Regular,
+            (true, false) => Self::Bold,
+            (false, true) => Self::Italic,
+            (true, true) => Self::BoldItalic,
+        }
+    }
+}
+
+pub struct FontFamily {
+    regular: Font,
+    bold: Option<Font>,
+    italic: Option<Font>,
+    bold_italic: Option<Font>,
+}
+
+impl FontFamily {
+    pub fn new(regular: Font) -> Self {
+        Self {
+            regular,
+            bold: None,
+            italic: None,
+            bold_italic: None,
+        }
+    }
+
+    pub fn load(regular: &CStr, bold: &CStr, italic: &CStr) -> Result<Self> {
+        Ok(Self::new(Font::new(regular)?)
+            .bold(Font::new(bold)?)
+            .italic(Font::new(italic)?))
+    }
+
+    pub fn bold(mut self, font: Font) -> Self {
+        self.bold = Some(font);
+        self
+    }
+
+    pub fn italic(mut self, font: Font) -> Self {
+        self.italic = Some(font);
+        self
+    }
+
+    pub fn bold_italic(mut self, font: Font) -> Self {
+        self.bold_italic = Some(font);
+        self
+    }
+
+    // Missing variants fall back to the closest one available, and finally to regular
+    pub fn font(&self, variant: FontVariant) -> &Font {
+        let font = match variant {
+            FontVariant::Regular => None,
+            FontVariant::Bold => self.bold.as_ref(),
+            FontVariant::Italic => self.italic.as_ref(),
+            FontVariant::BoldItalic => self
+                .bold_italic
+                .as_ref()
+                .or(self.bold.as_ref())
+                .or(self.italic.as_ref()),
+        };
+
+        font.unwrap_or(&self.regular)
+    }
+
+    fn height(&self) -> i32 {
+        [&self.bold, &self.italic, &self.bold_italic]
+            .into_iter()
+            .flatten()
+            .chain([&self.regular])
+            .map(|font| font.height() as i32)
+            .max()
+            .unwrap()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TextRun<'a> {
+    pub text: &'a str,
+    pub variant: FontVariant,
+}
+
+// Splits text into runs using the same markup as the Lua SDK: `*` toggles bold, `_`
+// toggles italic, and doubling either character produces it literally
+pub fn parse_markup(text: &str) -> Vec<TextRun<'_>> {
+    let mut runs = Vec::new();
+    let mut bold = false;
+    let mut italic = false;
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if c != '*' && c != '_' {
+            continue;
+        }
+
+        let variant = FontVariant::new(bold, italic);
+
+        if chars.next_if(|&(_, next)| next == c).is_some() {
+            // Keep the first of the pair as part of the current run and skip the second
+            push_run(&mut runs, &text[start..i + 1], variant);
+            start = i + 2;
+            continue;
+        }
+
+        push_run(&mut runs, &text[start..i], variant);
+        start = i + 1;
+
+        if c == '*' {
+            bold = !bold;
+        } else {
+            italic = !italic;
+        }
+    }
+
+    push_run(&mut runs, &text[start..], FontVariant::new(bold, italic));
+    runs
+}
+
+fn push_run<'a>(runs: &mut Vec<TextRun<'a>>, text: &'a str, variant: FontVariant) {
+    if !text.is_empty() {
+        runs.push(TextRun { text, variant });
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PlacedRun<'a> {
+    pub text: &'a str,
+    pub variant: FontVariant,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+}
+
+#[derive(Default)]
+struct Cursor {
+    x: i32,
+    y: i32,
+    space: i32,
+}
+
+impl Cursor {
+    fn new_line(&mut self, line_height: i32) {
+        self.x = 0;
+        self.y += line_height;
+        self.space = 0;
+    }
+}
+
+pub struct RichText<'a> {
+    family: &'a FontFamily,
+    max_width: Option<i32>,
+    tracking: i32,
+    leading: i32,
+}
+
+impl<'a> RichText<'a> {
+    pub fn new(family: &'a FontFamily) -> Self {
+        Self {
+            family,
+            max_width: None,
+            tracking: 0,
+            leading: 0,
+        }
+    }
+
+    // Wraps at spaces so that no line is wider than `max_width`, unless a single word is
+    pub fn max_width(mut self, max_width: i32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn tracking(mut self, tracking: i32) -> Self {
+        self.tracking = tracking;
+        self
+    }
+
+    pub fn leading(mut self, leading: i32) -> Self {
+        self.leading = leading;
+        self
+    }
+
+    // Measured glyph by glyph because kerning only applies within a run of the same font
+    fn width(&self, text: &str, variant: FontVariant) -> i32 {
+        let font = self.family.font(variant);
+        let mut chars = text.chars().peekable();
+        let mut width = 0;
+
+        while let Some(c) = chars.next() {
+            let next = chars.peek().copied();
+            width += font.char_advance(c, next);
+            if next.is_some() {
+                width += self.tracking;
+            }
+        }
+
+        width
+    }
+
+    fn line_height(&self) -> i32 {
+        self.family.height() + self.leading
+    }
+
+    // Lays out the markup relative to (0, 0)
+    pub fn layout<'t>(&self, text: &'t str) -> Vec<PlacedRun<'t>> {
+        let mut placed = Vec::new();
+        let mut word = Vec::new();
+        let mut cursor = Cursor::default();
+
+        for run in parse_markup(text) {
+            let mut rest = run.text;
+
+            while !rest.is_empty() {
+                let end = rest.find([' ', '\n']).unwrap_or(rest.len());
+
+                if end > 0 {
+                    let text = &rest[..end];
+                    let width = self.width(text, run.variant);
+                    word.push(PlacedRun {
+                        text,
+                        variant: run.variant,
+                        x: 0,
+                        y: 0,
+                        width,
+                    });
+                    rest = &rest[end..];
+                    continue;
+                }
+
+                self.place_word(&mut word, &mut cursor, &mut placed);
+
+                if rest.starts_with('\n') {
+                    cursor.new_line(self.line_height());
+                } else if cursor.x > 0 {
+                    cursor.space += self.width(" ", run.variant) + self.tracking;
+                }
+
+                rest = &rest[1..];
+            }
+        }
+
+        self.place_word(&mut word, &mut cursor, &mut placed);
+        placed
+    }
+
+    // Words can span several runs, e.g. "*bold*ness", so they're placed as a whole
+    fn place_word<'t>(
+        &self,
+        word: &mut Vec<PlacedRun<'t>>,
+        cursor: &mut Cursor,
+        placed: &mut Vec<PlacedRun<'t>>,
+    ) {
+        if word.is_empty() {
+            return;
+        }
+
+        let width: i32 = word.iter().map(|run| run.width).sum();
+        let fits = match self.max_width {
+            Some(max) => cursor.x == 0 || cursor.x + cursor.space + width <= max,
+            None => true,
+        };
+
+        if fits {
+            cursor.x += cursor.space;
+        } else {
+            cursor.new_line(self.line_height());
+        }
+
+        for mut run in word.drain(..) {
+            run.x = cursor.x;
+            run.y = cursor.y;
+            cursor.x += run.width;
+            placed.push(run);
+        }
+
+        cursor.space = 0;
+    }
+
+    // Returns the width and height of the text as laid out
+    pub fn measure(&self, text: &str) -> IntSize {
+        self.size(&self.layout(text))
+    }
+
+    pub fn draw(&self, gfx: &mut Graphics, text: &str, x: i32, y: i32) -> IntSize {
+        let runs = self.layout(text);
+
+        // Draw with the family's fonts and tracking, then put back whatever was set before
+        let mut gfx = gfx.scope();
+        gfx.set_text_tracking(self.tracking);
+
+        for run in &runs {
+            gfx.use_font(self.family.font(run.variant));
+            gfx.draw_str(run.text, x + run.x, y + run.y);
+        }
+
+        self.size(&runs)
+    }
+
+    fn size(&self, runs: &[PlacedRun]) -> IntSize {
+        let width = runs.iter().map(|run| run.x + run.width).max().unwrap_or(0);
+        let height = runs
+            .iter()
+            .map(|run| run.y + self.family.height())
+            .max()
+            .unwrap_or(0);
+
+        IntSize::new(width, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(text: &str) -> Vec<(&str, FontVariant)> {
+        parse_markup(text)
+            .into_iter()
+            .map(|run| (run.text, run.variant))
+            .collect()
+    }
+
+    #[test]
+    fn toggles_styles() {
+        assert_eq!(
+            runs("a *b* _c_ d"),
+            [
+                ("a ", FontVariant::Regular),
+                ("b", FontVariant::Bold),
+                (" ", FontVariant::Regular),
+                ("c", FontVariant::Italic),
+                (" d", FontVariant::Regular),
+            ]
+        );
+        assert_eq!(runs("plain"), [("plain", FontVariant::Regular)]);
+        assert!(runs("").is_empty());
+        assert_eq!(runs("**"), [("*", FontVariant::Regular)]);
+        assert!(runs("*_*_").is_empty());
+    }
+
+    #[test]
+    fn doubled_markers_are_literal() {
+        assert_eq!(
+            runs("2**3 = 8, a__b"),
+            [
+                ("2*", FontVariant::Regular),
+                ("3 = 8, a_", FontVariant::Regular),
+                ("b", FontVariant::Regular),
+            ]
+        );
+        assert_eq!(
+            runs("*x**y*"),
+            [("x*", FontVariant::Bold), ("y", FontVariant::Bold)]
+        );
+    }
+
+    #[test]
+    fn unterminated_styles_run_to_the_end() {
+        assert_eq!(
+            runs("a *bold to the end"),
+            [
+                ("a ", FontVariant::Regular),
+                ("bold to the end", FontVariant::Bold),
+            ]
+        );
+        assert_eq!(runs("_é"), [("é", FontVariant::Italic)]);
+    }
+
+    #[test]
+    fn adjacent_runs_change_style() {
+        assert_eq!(
+            runs("*b*_i_*_bi_*"),
+            [
+                ("b", FontVariant::Bold),
+                ("i", FontVariant::Italic),
+                ("bi", FontVariant::BoldItalic),
+            ]
+        );
+        assert_eq!(
+            runs("*b_bi*i_"),
+            [
+                ("b", FontVariant::Bold),
+                ("bi", FontVariant::BoldItalic),
+                ("i", FontVariant::Italic),
+            ]
+        );
+    }
+}
@@ -1,7 +1,11 @@
 use crate::{
+    animation::Animation,
     bitmap::{Bitmap, BitmapFlip},
-    gfx::{IntRect, Rect},
-    libc, Playdate,
+    easing::Easing,
+    geometry::{IntPoint, IntRect, Point, Rect},
+    libc,
+    tween::Tween,
+    Playdate,
 };
 use alloc::{boxed::Box, rc::Rc, vec::Vec};
 use core::{
@@ -12,12 +16,11 @@ use core::{
     ptr::null_mut,
 };
 use playdate_sys::{
-    CollisionPoint, CollisionVector, LCDBitmapDrawMode_kDrawModeBlackTransparent,
-    LCDBitmapDrawMode_kDrawModeCopy, LCDBitmapDrawMode_kDrawModeFillBlack,
-    LCDBitmapDrawMode_kDrawModeFillWhite, LCDBitmapDrawMode_kDrawModeInverted,
-    LCDBitmapDrawMode_kDrawModeNXOR, LCDBitmapDrawMode_kDrawModeWhiteTransparent,
-    LCDBitmapDrawMode_kDrawModeXOR, LCDSprite, SpriteCollisionResponseType,
-    SpriteCollisionResponseType_kCollisionTypeBounce,
+    LCDBitmapDrawMode_kDrawModeBlackTransparent, LCDBitmapDrawMode_kDrawModeCopy,
+    LCDBitmapDrawMode_kDrawModeFillBlack, LCDBitmapDrawMode_kDrawModeFillWhite,
+    LCDBitmapDrawMode_kDrawModeInverted, LCDBitmapDrawMode_kDrawModeNXOR,
+    LCDBitmapDrawMode_kDrawModeWhiteTransparent, LCDBitmapDrawMode_kDrawModeXOR, LCDSprite, PDRect,
+    SpriteCollisionResponseType, SpriteCollisionResponseType_kCollisionTypeBounce,
     SpriteCollisionResponseType_kCollisionTypeFreeze,
     SpriteCollisionResponseType_kCollisionTypeOverlap,
     SpriteCollisionResponseType_kCollisionTypeSlide,
@@ -46,7 +49,7 @@ impl<T> SpriteAPI<T> {
     }
 
     pub fn set_clip_rects_in_range(&mut self, rect: IntRect, start_z: i32, end_z: i32) {
-        invoke_unsafe!(sprite.setClipRectsInRange, rect, start_z, end_z)
+        invoke_unsafe!(sprite.setClipRectsInRange, rect.into(), start_z, end_z)
     }
 
     pub fn clear_clip_rects_in_range(&mut self, start_z: i32, end_z: i32) {
@@ -58,7 +61,7 @@ impl<T> SpriteAPI<T> {
     }
 
     pub fn add_dirty_rect(&mut self, rect: IntRect) {
-        invoke_unsafe!(sprite.addDirtyRect, rect)
+        invoke_unsafe!(sprite.addDirtyRect, rect.into())
     }
 
     pub fn sprite_count(&self) -> i32 {
@@ -87,12 +90,7 @@ impl<T> SpriteAPI<T> {
         // make any guarantees here as to if they are mutably borrowed elsewhere
         sprites
             .iter()
-            .filter(|sprite| {
-                let bounds = sprite.bounds();
-                let in_x = x >= bounds.x && x <= bounds.x + bounds.width;
-                let in_y = y >= bounds.y && y <= bounds.y + bounds.height;
-                in_x && in_y
-            })
+            .filter(|sprite| sprite.bounds().contains_inclusive(Point::new(x, y)))
             .collect()
     }
 
@@ -101,12 +99,7 @@ impl<T> SpriteAPI<T> {
         // make any guarantees here as to if they are mutably borrowed elsewhere
         sprites
             .iter()
-            .filter(|sprite| {
-                let bounds = sprite.bounds();
-                let in_x = bounds.x <= rect.x + rect.width && bounds.x + bounds.width >= rect.x;
-                let in_y = bounds.y <= rect.y + rect.height && bounds.y + bounds.height >= rect.y;
-                in_x && in_y
-            })
+            .filter(|sprite| sprite.bounds().intersects_inclusive(&rect))
             .collect()
     }
 
@@ -143,6 +136,8 @@ struct SpriteData<T> {
     displayed: bool,
     bitmap: Option<Rc<Bitmap>>,
     stencil: Option<SpriteStencil>,
+    movement: Option<Tween<Point>>,
+    animation: Option<Animation>,
     game_object: *mut dyn GameObject<T>,
 }
 
@@ -156,7 +151,44 @@ extern "C" fn update_callback<T: 'static>(ptr: *mut LCDSprite) {
     let mut sprite = ManuallyDrop::new(Sprite::from_ptr(ptr));
 
     let data_ptr = invoke_unsafe!(sprite.getUserdata, ptr) as *mut SpriteData<T>;
-    let go_ptr = unsafe { &*data_ptr }.game_object;
+
+    // The sprite's setters borrow its data again, so this borrow has to end before they run
+    let (position, image, go_ptr) = {
+        let data = unsafe { &mut *data_ptr };
+
+        let position = data.movement.as_mut().map(|movement| {
+            let position = movement.update(pd.system());
+            (position, movement.is_finished())
+        });
+        if let Some((_, true)) = position {
+            data.movement = None;
+        }
+
+        let image = data.animation.as_mut().and_then(|animation| {
+            let index = animation.frame_index();
+            let image = animation.update(pd.system());
+            image.filter(|_| animation.frame_index() != index)
+        });
+        if data.animation.as_ref().is_some_and(Animation::is_finished) {
+            data.animation = None;
+        }
+
+        (
+            position.map(|(position, _)| position),
+            image,
+            data.game_object,
+        )
+    };
+
+    if let Some(Point { x, y }) = position {
+        sprite.move_to(x, y);
+    }
+
+    if let Some(image) = image {
+        let flip = sprite.image_flip();
+        sprite.set_image(image, flip);
+    }
+
     let go = unsafe { &mut *go_ptr };
 
     let ctx = UpdateContext {
@@ -191,7 +223,7 @@ extern "C" fn collide_callback<T: 'static>(sprite: *mut LCDSprite, other: *mut L
     go.collide(ctx) as u32
 }
 
-extern "C" fn draw_callback<T: 'static>(ptr: *mut LCDSprite, bounds: Rect, draw_rect: Rect) {
+extern "C" fn draw_callback<T: 'static>(ptr: *mut LCDSprite, bounds: PDRect, draw_rect: PDRect) {
     let mut pd = unsafe { Playdate::init() };
     let sprite = ManuallyDrop::new(Sprite::from_ptr(ptr));
 
@@ -199,6 +231,8 @@ extern "C" fn draw_callback<T: 'static>(ptr: *mut LCDSprite, bounds: Rect, draw_
     let go_ptr = unsafe { &*data_ptr }.game_object;
     let go = unsafe { &mut *go_ptr };
 
+    let bounds = bounds.into();
+    let draw_rect = draw_rect.into();
     let ctx = DrawContext {
         sprite: &sprite,
         bounds: &bounds,
@@ -228,6 +262,8 @@ where
             displayed: false,
             stencil: None,
             bitmap: None,
+            movement: None,
+            animation: None,
             game_object,
         });
 
@@ -276,11 +312,11 @@ where
     }
 
     pub fn set_bounds(&mut self, bounds: Rect) {
-        invoke_unsafe!(sprite.setBounds, self.ptr, bounds)
+        invoke_unsafe!(sprite.setBounds, self.ptr, bounds.into())
     }
 
     pub fn bounds(&self) -> Rect {
-        invoke_unsafe!(sprite.getBounds, self.ptr)
+        invoke_unsafe!(sprite.getBounds, self.ptr).into()
     }
 
     pub fn move_to(&mut self, x: f32, y: f32) {
@@ -311,6 +347,41 @@ where
         invoke_unsafe!(sprite.setCenter, self.ptr, x, y)
     }
 
+    pub fn animate_move(&mut self, tween: Tween<Point>) {
+        self.data_mut().movement = Some(tween);
+    }
+
+    pub fn animate_move_to(&mut self, x: f32, y: f32, duration: u32, easing: Easing) {
+        let tween = Tween::new(self.position(), Point { x, y }, duration).easing(easing);
+        self.animate_move(tween);
+    }
+
+    pub fn stop_animation(&mut self) {
+        self.data_mut().movement = None;
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.data().movement.is_some()
+    }
+
+    // Plays the animation's frames as this sprite's image. Once it finishes the last frame
+    // stays in place.
+    pub fn animate_image(&mut self, animation: Animation, flip: BitmapFlip) {
+        if let Some(image) = animation.frame() {
+            self.set_image(image, flip);
+        }
+
+        self.data_mut().animation = Some(animation);
+    }
+
+    pub fn stop_image_animation(&mut self) {
+        self.data_mut().animation = None;
+    }
+
+    pub fn is_animating_image(&self) -> bool {
+        self.data().animation.is_some()
+    }
+
     pub fn set_image(&mut self, image: Rc<Bitmap>, flip: BitmapFlip) {
         let data = self.data_mut();
         let bmp = image.as_mut_ptr();
@@ -404,7 +475,7 @@ where
     }
 
     pub fn set_clip_rect(&mut self, clip_rect: IntRect) {
-        invoke_unsafe!(sprite.setClipRect, self.ptr, clip_rect)
+        invoke_unsafe!(sprite.setClipRect, self.ptr, clip_rect.into())
     }
 
     pub fn clear_clip_rect(&mut self) {
@@ -463,11 +534,11 @@ where
     }
 
     pub fn set_collide_rect(&mut self, rect: Rect) {
-        invoke_unsafe!(sprite.setCollideRect, self.ptr, rect)
+        invoke_unsafe!(sprite.setCollideRect, self.ptr, rect.into())
     }
 
     pub fn collide_rect(&self) -> Rect {
-        invoke_unsafe!(sprite.getCollideRect, self.ptr)
+        invoke_unsafe!(sprite.getCollideRect, self.ptr).into()
     }
 
     pub fn clear_collide_rect(&self) {
@@ -604,6 +675,11 @@ where
         self
     }
 
+    pub fn animation(mut self, animation: Animation, flip: BitmapFlip) -> Self {
+        self.sprite.animate_image(animation, flip);
+        self
+    }
+
     pub fn move_to(mut self, x: f32, y: f32) -> Self {
         self.sprite.move_to(x, y);
         self
@@ -750,9 +826,6 @@ impl<T> Drop for Sprite<T> {
     }
 }
 
-pub type Point = CollisionPoint;
-pub type IntPoint = CollisionVector;
-
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum DrawMode {
@@ -1,13 +1,18 @@
-use crate::bitmap::Bitmap;
-use alloc::boxed::Box;
+use crate::{
+    bitmap::Bitmap,
+    time::{DateTime, HourFormat},
+};
+use alloc::{borrow::ToOwned, boxed::Box, ffi::CString, vec::Vec};
 use bitflags::bitflags;
 use core::{
     ffi::{c_char, c_void, CStr},
+    marker::PhantomData,
     mem::MaybeUninit,
+    time::Duration,
 };
 use playdate_sys::{
     PDButtons, PDButtons_kButtonA, PDButtons_kButtonB, PDButtons_kButtonDown,
-    PDButtons_kButtonLeft, PDButtons_kButtonRight, PDButtons_kButtonUp,
+    PDButtons_kButtonLeft, PDButtons_kButtonRight, PDButtons_kButtonUp, PDDateTime,
     PDLanguage_kPDLanguageEnglish, PDLanguage_kPDLanguageJapanese, PDLanguage_kPDLanguageUnknown,
     PDMenuItem, PDPeripherals_kAccelerometer,
 };
@@ -69,14 +74,46 @@ impl System {
     pub fn add_options_menu_item<C>(
         &mut self,
         title: &CStr,
-        options: &[*const core::ffi::c_char],
+        options: &[&CStr],
         callback: C,
     ) -> OptionsMenuItem
     where
         C: FnMut() + 'static,
     {
+        let titles = options.iter().map(|&option| option.to_owned()).collect();
+        self.add_options(title, titles, callback)
+    }
+
+    pub fn add_typed_options_menu_item<O, C>(
+        &mut self,
+        title: &CStr,
+        callback: C,
+    ) -> OptionsMenuItem<O>
+    where
+        O: MenuOptions,
+        C: FnMut() + 'static,
+    {
+        let titles = O::titles()
+            .iter()
+            .map(|&option| option.to_owned())
+            .collect();
+        self.add_options(title, titles, callback)
+    }
+
+    fn add_options<O, C>(
+        &mut self,
+        title: &CStr,
+        titles: Vec<CString>,
+        callback: C,
+    ) -> OptionsMenuItem<O>
+    where
+        O: MenuValue,
+        C: FnMut() + 'static,
+    {
+        // The SDK keeps hold of the option strings, so they're owned by the menu item
+        // and only released once it has been removed
+        let options: Vec<*const c_char> = titles.iter().map(|option| option.as_ptr()).collect();
         let user_data = Box::into_raw(Box::new(callback)) as _;
-        let len = options.len();
         let ptr = invoke_unsafe!(
             system.addOptionsMenuItem,
             title.as_ptr(),
@@ -87,9 +124,11 @@ impl System {
         );
 
         OptionsMenuItem {
-            len,
             ptr,
             user_data,
+            titles,
+            options,
+            unused: Default::default(),
         }
     }
 
@@ -100,10 +139,17 @@ impl System {
     pub fn seconds_since_epoch(&self) -> Duration {
         let mut milliseconds = 0;
         let seconds = invoke_unsafe!(system.getSecondsSinceEpoch, &mut milliseconds);
-        Duration {
-            seconds,
-            milliseconds,
-        }
+        Duration::from_secs(seconds as u64) + Duration::from_millis(milliseconds as u64)
+    }
+
+    pub fn current_datetime(&self) -> DateTime {
+        let seconds = self.seconds_since_epoch().as_secs();
+        DateTime::from_timestamp(seconds as i64)
+    }
+
+    pub fn local_datetime(&self) -> DateTime {
+        self.current_datetime()
+            .with_timezone_offset(self.timezone_offset())
     }
 
     pub fn reset_elapsed_time(&mut self) {
@@ -119,22 +165,28 @@ impl System {
     }
 
     pub fn convert_epoch_to_datetime(&self, epoch: u32) -> DateTime {
-        let mut datetime = MaybeUninit::<DateTime>::uninit();
+        let mut datetime = MaybeUninit::<PDDateTime>::uninit();
         invoke_unsafe!(system.convertEpochToDateTime, epoch, datetime.as_mut_ptr());
-        unsafe { datetime.assume_init() }
+        unsafe { datetime.assume_init() }.into()
     }
 
     pub fn convert_datetime_to_epoch(&self, datetime: &DateTime) -> u32 {
-        invoke_unsafe!(
-            system.convertDateTimeToEpoch,
-            datetime as *const _ as *mut _
-        )
+        let mut datetime = PDDateTime::from(*datetime);
+        invoke_unsafe!(system.convertDateTimeToEpoch, &mut datetime)
     }
 
     pub fn should_display_24_hour_time(&self) -> bool {
         invoke_unsafe!(system.shouldDisplay24HourTime) == 1
     }
 
+    pub fn hour_format(&self) -> HourFormat {
+        if self.should_display_24_hour_time() {
+            HourFormat::TwentyFourHour
+        } else {
+            HourFormat::TwelveHour
+        }
+    }
+
     pub fn flipped(&self) -> bool {
         invoke_unsafe!(system.getFlipped) == 1
     }
@@ -245,12 +297,10 @@ impl System {
 
     pub fn language(&self) -> Language {
         let lang = invoke_unsafe!(system.getLanguage);
-        lang.try_into().unwrap()
+        lang.into()
     }
 }
 
-pub type DateTime = ::playdate_sys::PDDateTime;
-
 #[repr(i32)]
 #[derive(Clone, Copy, Debug)]
 pub enum Checked {
@@ -281,10 +331,33 @@ pub struct CheckmarkMenuItem {
     user_data: *mut c_void,
 }
 
-pub struct OptionsMenuItem {
+pub struct OptionsMenuItem<O = usize> {
     ptr: *mut PDMenuItem,
-    len: usize,
     user_data: *mut c_void,
+    titles: Vec<CString>,
+    options: Vec<*const c_char>,
+    unused: PhantomData<O>,
+}
+
+// Maps the index the SDK reports for an options menu item to a value and back
+pub trait MenuValue: Sized {
+    fn from_index(index: usize) -> Option<Self>;
+    fn to_index(&self) -> usize;
+}
+
+impl MenuValue for usize {
+    fn from_index(index: usize) -> Option<Self> {
+        Some(index)
+    }
+
+    fn to_index(&self) -> usize {
+        *self
+    }
+}
+
+// Usually derived with `#[derive(MenuOptions)]` from playdate_init on a fieldless enum
+pub trait MenuOptions: MenuValue {
+    fn titles() -> &'static [&'static CStr];
 }
 
 impl CheckmarkMenuItem {
@@ -297,18 +370,27 @@ impl CheckmarkMenuItem {
     }
 }
 
-impl OptionsMenuItem {
-    pub fn value(&self) -> usize {
-        invoke_unsafe!(system.getMenuItemValue, self.ptr) as usize
+impl<O> OptionsMenuItem<O>
+where
+    O: MenuValue,
+{
+    pub fn value(&self) -> O {
+        let index = invoke_unsafe!(system.getMenuItemValue, self.ptr) as usize;
+        O::from_index(index).expect("menu item index out of bounds")
     }
 
-    pub fn set_value(&mut self, index: usize) {
-        if index >= self.len {
+    pub fn set_value(&mut self, value: O) {
+        let index = value.to_index();
+        if index >= self.options.len() {
             panic!("menu item index out of bounds")
         }
 
         invoke_unsafe!(system.setMenuItemValue, self.ptr, index as i32)
     }
+
+    pub fn options(&self) -> &[CString] {
+        &self.titles
+    }
 }
 
 impl MenuItem for ButtonMenuItem {
@@ -323,7 +405,7 @@ impl MenuItem for CheckmarkMenuItem {
     }
 }
 
-impl MenuItem for OptionsMenuItem {
+impl<O> MenuItem for OptionsMenuItem<O> {
     fn as_mut_ptr(&self) -> *mut PDMenuItem {
         self.ptr
     }
@@ -343,18 +425,13 @@ impl Drop for CheckmarkMenuItem {
     }
 }
 
-impl Drop for OptionsMenuItem {
+impl<O> Drop for OptionsMenuItem<O> {
     fn drop(&mut self) {
         unsafe { drop(Box::from_raw(self.user_data)) };
         invoke_unsafe!(system.removeMenuItem, self.ptr)
     }
 }
 
-pub struct Duration {
-    pub seconds: u32,
-    pub milliseconds: u32,
-}
-
 bitflags! {
     pub struct Peripherals: u32 {
         const ACCELEROMETER = PDPeripherals_kAccelerometer;
@@ -362,6 +439,7 @@ bitflags! {
 }
 
 bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
     pub struct ButtonState: u32 {
         const LEFT = PDButtons_kButtonLeft;
         const RIGHT = PDButtons_kButtonRight;
@@ -403,25 +481,33 @@ pub enum CrankSoundState {
     Disabled = 1,
 }
 
-#[repr(u32)]
 #[derive(Clone, Copy, PartialEq, Debug, Eq)]
 pub enum Language {
-    English = PDLanguage_kPDLanguageEnglish,
-    Japanese = PDLanguage_kPDLanguageJapanese,
-    Unknown = PDLanguage_kPDLanguageUnknown,
+    English,
+    Japanese,
+    Unknown,
+    Other(u32),
 }
 
-impl TryFrom<u32> for Language {
-    type Error = ();
+impl Language {
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            Self::English => Some("en"),
+            Self::Japanese => Some("ja"),
+            Self::Unknown | Self::Other(_) => None,
+        }
+    }
+}
 
+impl From<u32> for Language {
     #[allow(non_upper_case_globals)]
-    fn try_from(value: u32) -> Result<Self, Self::Error> {
-        Ok(match value {
+    fn from(value: u32) -> Self {
+        match value {
             PDLanguage_kPDLanguageEnglish => Self::English,
             PDLanguage_kPDLanguageJapanese => Self::Japanese,
             PDLanguage_kPDLanguageUnknown => Self::Unknown,
-            _ => Err(())?,
-        })
+            other => Self::Other(other),
+        }
     }
 }
 
@@ -0,0 +1,181 @@
+use crate::system::{ButtonState, System};
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    future::Future,
+    mem,
+    pin::Pin,
+    ptr::{addr_of_mut, null},
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+type Task = Pin<Box<dyn Future<Output = ()>>>;
+
+struct Executor {
+    tasks: Vec<Task>,
+    spawned: Vec<Task>,
+}
+
+// Tasks are polled from the update callback, so they have to outlive any single
+// `Playdate` instance just like the app data does
+static mut EXECUTOR: Executor = Executor {
+    tasks: Vec::new(),
+    spawned: Vec::new(),
+};
+
+fn executor() -> &'static mut Executor {
+    unsafe { &mut *addr_of_mut!(EXECUTOR) }
+}
+
+pub(crate) fn spawn<F>(future: F)
+where
+    F: Future<Output = ()> + 'static,
+{
+    executor().spawned.push(Box::pin(future));
+}
+
+pub(crate) fn poll_tasks() {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    // Anything spawned while polling is queued in `spawned` and picked up next frame
+    let mut tasks = mem::take(&mut executor().tasks);
+    tasks.append(&mut executor().spawned);
+    tasks.retain_mut(|task| task.as_mut().poll(&mut cx).is_pending());
+    executor().tasks = tasks;
+}
+
+pub(crate) fn task_count() -> usize {
+    let executor = executor();
+    executor.tasks.len() + executor.spawned.len()
+}
+
+// Every task is polled once per frame anyway, so wakers never need to do anything
+fn noop_waker() -> Waker {
+    const VTABLE: RawWakerVTable =
+        RawWakerVTable::new(|_| RawWaker::new(null(), &VTABLE), |_| {}, |_| {}, |_| {});
+
+    unsafe { Waker::from_raw(RawWaker::new(null(), &VTABLE)) }
+}
+
+pub fn next_frame() -> WaitFrames {
+    wait_frames(1)
+}
+
+pub fn wait_frames(frames: u32) -> WaitFrames {
+    WaitFrames { remaining: frames }
+}
+
+pub fn wait_ms(milliseconds: u32) -> WaitMs {
+    WaitMs {
+        milliseconds,
+        started_at: None,
+    }
+}
+
+pub fn wait_button(buttons: ButtonState) -> WaitButton {
+    WaitButton { buttons }
+}
+
+pub fn wait_crank_turns(turns: f32) -> WaitCrankTurns {
+    WaitCrankTurns {
+        degrees: turns * 360.0,
+        turned: 0.0,
+        last_angle: None,
+    }
+}
+
+pub struct WaitFrames {
+    remaining: u32,
+}
+
+impl Future for WaitFrames {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.remaining == 0 {
+            return Poll::Ready(());
+        }
+
+        self.remaining -= 1;
+        Poll::Pending
+    }
+}
+
+pub struct WaitMs {
+    milliseconds: u32,
+    started_at: Option<u32>,
+}
+
+impl Future for WaitMs {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let now = System::new().current_time_milliseconds();
+        let started_at = *self.started_at.get_or_insert(now);
+
+        if now.wrapping_sub(started_at) >= self.milliseconds {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+pub struct WaitButton {
+    buttons: ButtonState,
+}
+
+impl Future for WaitButton {
+    type Output = ButtonState;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let pushed = System::new().button_state().pushed;
+
+        if pushed.intersects(self.buttons) {
+            Poll::Ready(pushed & self.buttons)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+pub struct WaitCrankTurns {
+    degrees: f32,
+    turned: f32,
+    last_angle: Option<f32>,
+}
+
+impl Future for WaitCrankTurns {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Track the absolute angle rather than using crank_change() so that waiting on the
+        // crank doesn't swallow the change the game itself reads each frame
+        let angle = System::new().crank_angle();
+
+        if let Some(last_angle) = self.last_angle {
+            let mut delta = angle - last_angle;
+            if delta > 180.0 {
+                delta -= 360.0;
+            } else if delta < -180.0 {
+                delta += 360.0;
+            }
+
+            self.turned += delta;
+        }
+
+        self.last_angle = Some(angle);
+
+        let done = if self.degrees >= 0.0 {
+            self.turned >= self.degrees
+        } else {
+            self.turned <= self.degrees
+        };
+
+        if done {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
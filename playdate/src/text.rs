@@ -0,0 +1,217 @@
+use crate::{
+    font::Font,
+    geometry::{IntSize, Rect},
+    gfx::Graphics,
+};
+use alloc::vec::Vec;
+
+const ELLIPSIS: &str = "...";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TextAlignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TextLine<'a> {
+    pub text: &'a str,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub truncated: bool,
+}
+
+pub struct TextLayout<'a> {
+    font: &'a Font,
+    rect: Rect,
+    alignment: TextAlignment,
+    tracking: i32,
+    leading: i32,
+    truncate: bool,
+}
+
+impl<'a> TextLayout<'a> {
+    pub fn new(font: &'a Font, rect: Rect) -> Self {
+        Self {
+            font,
+            rect,
+            alignment: Default::default(),
+            tracking: 0,
+            leading: 0,
+            truncate: false,
+        }
+    }
+
+    pub fn alignment(mut self, alignment: TextAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn tracking(mut self, tracking: i32) -> Self {
+        self.tracking = tracking;
+        self
+    }
+
+    pub fn leading(mut self, leading: i32) -> Self {
+        self.leading = leading;
+        self
+    }
+
+    // Drops lines that don't fit in the rect and ends the last visible one with an ellipsis
+    pub fn truncate(mut self, truncate: bool) -> Self {
+        self.truncate = truncate;
+        self
+    }
+
+    fn width(&self, text: &str) -> i32 {
+        self.font.str_width(text, self.tracking)
+    }
+
+    fn line_height(&self) -> i32 {
+        self.font.height() as i32 + self.leading
+    }
+
+    pub fn lines<'t>(&self, text: &'t str) -> Vec<TextLine<'t>> {
+        let max_width = self.rect.width as i32;
+        let mut lines = Vec::new();
+
+        for paragraph in text.split('\n') {
+            self.wrap(paragraph.trim_end_matches('\r'), max_width, &mut lines);
+        }
+
+        let line_height = self.line_height();
+        let max_lines = ((self.rect.height as i32 + self.leading) / line_height).max(1) as usize;
+
+        if self.truncate && lines.len() > max_lines {
+            lines.truncate(max_lines);
+            let last = lines.last_mut().unwrap();
+            last.text = self.fit_with_ellipsis(last.text, max_width);
+            last.truncated = true;
+        }
+
+        for (i, line) in lines.iter_mut().enumerate() {
+            line.width = self.width(line.text);
+            if line.truncated {
+                line.width += self.tracking + self.width(ELLIPSIS);
+            }
+
+            let free = max_width - line.width;
+            let offset = match self.alignment {
+                TextAlignment::Left => 0,
+                TextAlignment::Center => free / 2,
+                TextAlignment::Right => free,
+            };
+
+            line.x = self.rect.x as i32 + offset;
+            line.y = self.rect.y as i32 + i as i32 * line_height;
+        }
+
+        lines
+    }
+
+    // Greedily fills each line with as many words as fit, breaking words that are wider
+    // than the rect on their own
+    fn wrap<'t>(&self, paragraph: &'t str, max_width: i32, lines: &mut Vec<TextLine<'t>>) {
+        let mut rest = paragraph;
+
+        if rest.is_empty() {
+            lines.push(line(rest));
+            return;
+        }
+
+        while !rest.is_empty() {
+            if self.width(rest) <= max_width {
+                lines.push(line(rest));
+                break;
+            }
+
+            let mut split = None;
+            for (i, _) in rest.match_indices(' ') {
+                let candidate = rest[..i].trim_end();
+                if candidate.is_empty() {
+                    continue;
+                }
+
+                if self.width(candidate) > max_width {
+                    break;
+                }
+
+                split = Some(i);
+            }
+
+            let (text, next) = match split {
+                Some(i) => (rest[..i].trim_end(), rest[i..].trim_start()),
+                None => {
+                    let mut end = rest.chars().next().map_or(0, char::len_utf8);
+                    for (i, _) in rest.char_indices().skip(1) {
+                        if self.width(&rest[..i]) > max_width {
+                            break;
+                        }
+                        end = i;
+                    }
+
+                    (&rest[..end], &rest[end..])
+                }
+            };
+
+            lines.push(line(text));
+            rest = next;
+        }
+    }
+
+    fn fit_with_ellipsis<'t>(&self, text: &'t str, max_width: i32) -> &'t str {
+        let available = max_width - self.width(ELLIPSIS) - self.tracking;
+        let mut text = text.trim_end();
+
+        while !text.is_empty() && self.width(text) > available {
+            let end = text.char_indices().last().map_or(0, |(i, _)| i);
+            text = text[..end].trim_end();
+        }
+
+        text
+    }
+
+    // Returns the width and height of the text as laid out
+    pub fn measure(&self, text: &str) -> IntSize {
+        size(&self.lines(text), self.line_height(), self.leading)
+    }
+
+    pub fn draw(&self, gfx: &mut Graphics, text: &str) -> IntSize {
+        let lines = self.lines(text);
+
+        // Draw with the layout's font and tracking, then put back whatever was set before
+        let mut gfx = gfx.scope();
+        gfx.use_font(self.font);
+        gfx.set_text_tracking(self.tracking);
+
+        for line in &lines {
+            gfx.draw_str(line.text, line.x, line.y);
+
+            if line.truncated {
+                let x = line.x + line.width - self.width(ELLIPSIS);
+                gfx.draw_str(ELLIPSIS, x, line.y);
+            }
+        }
+
+        size(&lines, self.line_height(), self.leading)
+    }
+}
+
+fn line(text: &str) -> TextLine<'_> {
+    TextLine {
+        text,
+        x: 0,
+        y: 0,
+        width: 0,
+        truncated: false,
+    }
+}
+
+fn size(lines: &[TextLine], line_height: i32, leading: i32) -> IntSize {
+    let width = lines.iter().map(|line| line.width).max().unwrap_or(0);
+    let height = (lines.len() as i32 * line_height - leading).max(0);
+    IntSize::new(width, height)
+}
@@ -0,0 +1,354 @@
+use crate::{
+    bitmap::{BitmapFlip, BitmapTable},
+    geometry::{IntPoint, IntRect, IntSize, Rect},
+    sprite::{GameObject, Sprite, SpriteAPI, SpriteBuilder, UpdatesState, Visibility},
+    Playdate,
+};
+use alloc::{boxed::Box, rc::Rc, vec, vec::Vec};
+use playdate_sys::{LCD_COLUMNS, LCD_ROWS};
+
+const SCREEN: IntRect = IntRect::new(0, 0, LCD_COLUMNS as i32, LCD_ROWS as i32);
+
+// A grid of tiles from a bitmap table. Tiles are drawn at their position in the map minus the
+// scroll offset, so the map's top left corner is at the top left of the screen when it isn't
+// scrolled.
+pub struct TileMap {
+    tileset: Rc<BitmapTable>,
+    tile_size: IntSize,
+    columns: i32,
+    rows: i32,
+    // Row by row, with `None` for empty tiles
+    tiles: Vec<Option<u16>>,
+    scroll: IntPoint,
+    dirty: Vec<IntRect>,
+}
+
+impl TileMap {
+    // Rows that would take the map past i32::MAX tiles are dropped, since tiles are indexed
+    // with i32
+    pub fn new(tileset: Rc<BitmapTable>, columns: i32, rows: i32) -> Self {
+        let columns = columns.max(0);
+        let rows = rows.clamp(0, i32::MAX / columns.max(1));
+        Self::from_tiles(tileset, columns, vec![None; (columns * rows) as usize])
+    }
+
+    // Each row of `tiles` is `columns` long, and a partial last row is dropped
+    pub fn from_tiles(tileset: Rc<BitmapTable>, columns: i32, mut tiles: Vec<Option<u16>>) -> Self {
+        let columns = columns.max(0);
+        let rows = if columns > 0 {
+            (tiles.len() / columns as usize).min((i32::MAX / columns) as usize) as i32
+        } else {
+            0
+        };
+        tiles.truncate((columns * rows) as usize);
+
+        // Tilesets that fail to load have no frames, and nothing gets drawn
+        let tile_size = tileset.frame_size().unwrap_or_default();
+
+        Self {
+            tileset,
+            tile_size,
+            columns,
+            rows,
+            tiles,
+            scroll: IntPoint::new(0, 0),
+            dirty: Vec::new(),
+        }
+    }
+
+    pub fn tileset(&self) -> &Rc<BitmapTable> {
+        &self.tileset
+    }
+
+    pub fn tile_size(&self) -> IntSize {
+        self.tile_size
+    }
+
+    pub fn columns(&self) -> i32 {
+        self.columns
+    }
+
+    pub fn rows(&self) -> i32 {
+        self.rows
+    }
+
+    // The size of the whole map in pixels
+    pub fn size(&self) -> IntSize {
+        IntSize::new(
+            self.columns * self.tile_size.width,
+            self.rows * self.tile_size.height,
+        )
+    }
+
+    pub fn tiles(&self) -> &[Option<u16>] {
+        &self.tiles
+    }
+
+    pub fn tile(&self, column: i32, row: i32) -> Option<u16> {
+        self.index(column, row).and_then(|i| self.tiles[i])
+    }
+
+    pub fn set_tile(&mut self, column: i32, row: i32, tile: Option<u16>) -> bool {
+        let Some(i) = self.index(column, row) else {
+            return false;
+        };
+
+        if self.tiles[i] != tile {
+            self.tiles[i] = tile;
+            let rect = self.tile_rect(column, row);
+            self.mark_dirty(rect.offset(-self.scroll.x, -self.scroll.y));
+        }
+
+        true
+    }
+
+    // The area of a tile in map coordinates
+    pub fn tile_rect(&self, column: i32, row: i32) -> IntRect {
+        IntRect::new(
+            column * self.tile_size.width,
+            row * self.tile_size.height,
+            self.tile_size.width,
+            self.tile_size.height,
+        )
+    }
+
+    // The column and row of the tile under a point in map coordinates
+    pub fn tile_at(&self, point: IntPoint) -> Option<(i32, i32)> {
+        if self.tile_size.is_empty() {
+            return None;
+        }
+
+        let column = point.x.div_euclid(self.tile_size.width);
+        let row = point.y.div_euclid(self.tile_size.height);
+        self.index(column, row).map(|_| (column, row))
+    }
+
+    pub fn scroll_offset(&self) -> IntPoint {
+        self.scroll
+    }
+
+    pub fn set_scroll_offset(&mut self, offset: IntPoint) {
+        if offset == self.scroll {
+            return;
+        }
+
+        // Everything on screen moves, so both where the map was and where it is now change
+        let previous = self.bounds();
+        self.scroll = offset;
+        self.mark_dirty(previous);
+        self.mark_dirty(self.bounds());
+    }
+
+    // Where the map is on screen
+    pub fn bounds(&self) -> IntRect {
+        IntRect::from_origin_size(IntPoint::new(-self.scroll.x, -self.scroll.y), self.size())
+    }
+
+    // Hands the areas that changed since the last call to the sprite system, so that only
+    // sprites over them are redrawn
+    pub fn flush_dirty_rects<T>(&mut self, sprites: &mut SpriteAPI<T>) {
+        for rect in self.dirty.drain(..) {
+            sprites.add_dirty_rect(rect);
+        }
+    }
+
+    // Draws the tiles that overlap `rect`, which is in screen coordinates. Sprites should
+    // pass the draw rect they're given.
+    pub fn draw(&self, rect: IntRect) {
+        if self.tile_size.is_empty() {
+            return;
+        }
+
+        let Some(rect) = rect.intersection(&self.bounds()) else {
+            return;
+        };
+
+        let IntSize { width, height } = self.tile_size;
+        let (sx, sy) = (self.scroll.x, self.scroll.y);
+        let columns = (rect.x + sx) / width..(rect.max_x() + sx + width - 1) / width;
+        let rows = (rect.y + sy) / height..(rect.max_y() + sy + height - 1) / height;
+
+        for row in rows {
+            for column in columns.clone() {
+                let Some(frame) = self
+                    .tile(column, row)
+                    .and_then(|tile| self.tileset.get(tile as usize))
+                else {
+                    continue;
+                };
+
+                frame.draw(
+                    column * width - sx,
+                    row * height - sy,
+                    BitmapFlip::Unflipped,
+                );
+            }
+        }
+    }
+
+    // Covers the tiles for which `solid` returns true with as few rects as it can, in map
+    // coordinates
+    pub fn collision_rects<F>(&self, solid: F) -> Vec<Rect>
+    where
+        F: Fn(u16) -> bool,
+    {
+        let is_solid = |column, row| self.tile(column, row).is_some_and(&solid);
+
+        let IntSize { width, height } = self.tile_size;
+        merge_tiles(self.columns, self.rows, is_solid)
+            .into_iter()
+            .map(|r| {
+                IntRect::new(
+                    r.x * width,
+                    r.y * height,
+                    r.width * width,
+                    r.height * height,
+                )
+            })
+            .map(Rect::from)
+            .collect()
+    }
+
+    // Adds a `TileCollider` for each of the collision rects
+    pub fn add_colliders<T, F>(&self, sprites: &mut SpriteAPI<T>, tag: u8, solid: F)
+    where
+        F: Fn(u16) -> bool,
+    {
+        for rect in self.collision_rects(solid) {
+            sprites.new_sprite(Box::new(TileCollider::new(rect).tag(tag)));
+        }
+    }
+
+    fn index(&self, column: i32, row: i32) -> Option<usize> {
+        let in_bounds = (0..self.columns).contains(&column) && (0..self.rows).contains(&row);
+        in_bounds.then(|| (row * self.columns + column) as usize)
+    }
+
+    fn mark_dirty(&mut self, rect: IntRect) {
+        if let Some(rect) = rect.intersection(&SCREEN) {
+            self.dirty.push(rect);
+        }
+    }
+}
+
+// Runs of solid tiles in each row are merged, and then runs spanning the same columns in
+// consecutive rows. The rects are in tiles.
+fn merge_tiles<F>(columns: i32, rows: i32, is_solid: F) -> Vec<IntRect>
+where
+    F: Fn(i32, i32) -> bool,
+{
+    let mut done = Vec::new();
+    let mut open: Vec<IntRect> = Vec::new();
+
+    for row in 0..rows {
+        let mut next = Vec::new();
+        let mut column = 0;
+
+        while column < columns {
+            if !is_solid(column, row) {
+                column += 1;
+                continue;
+            }
+
+            let start = column;
+            while column < columns && is_solid(column, row) {
+                column += 1;
+            }
+
+            let width = column - start;
+            let rect = match open.iter().position(|r| r.x == start && r.width == width) {
+                Some(i) => {
+                    let mut rect = open.swap_remove(i);
+                    rect.height += 1;
+                    rect
+                }
+                None => IntRect::new(start, row, width, 1),
+            };
+            next.push(rect);
+        }
+
+        done.append(&mut open);
+        open = next;
+    }
+
+    done.append(&mut open);
+    done
+}
+
+// An invisible sprite that doesn't move, for other sprites to collide with
+pub struct TileCollider {
+    rect: Rect,
+    tag: u8,
+}
+
+impl TileCollider {
+    pub fn new(rect: Rect) -> Self {
+        Self { rect, tag: 0 }
+    }
+
+    pub fn tag(mut self, tag: u8) -> Self {
+        self.tag = tag;
+        self
+    }
+}
+
+impl<T> GameObject<T> for TileCollider
+where
+    T: 'static,
+{
+    fn init(&mut self, builder: SpriteBuilder<T>, _pd: &mut Playdate<T>) -> Sprite<T> {
+        let collide_rect = Rect::new(0.0, 0.0, self.rect.width, self.rect.height);
+        let mut sprite = builder
+            .bounds(self.rect)
+            .collide_rect(collide_rect)
+            .tag(self.tag)
+            .add()
+            .build();
+
+        sprite.set_visible(Visibility::Invisible);
+        sprite.set_updates_enabled(UpdatesState::Disabled);
+        sprite
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(map: &[&str]) -> Vec<IntRect> {
+        let columns = map.first().map_or(0, |row| row.len()) as i32;
+        let mut rects = merge_tiles(columns, map.len() as i32, |column, row| {
+            map[row as usize].as_bytes()[column as usize] == b'#'
+        });
+        rects.sort_by_key(|r| (r.y, r.x));
+        rects
+    }
+
+    #[test]
+    fn merges_rows_and_columns() {
+        let rects = merge(&["##..#", "##..#", "....#", "###.."]);
+
+        assert_eq!(
+            rects,
+            [
+                IntRect::new(0, 0, 2, 2),
+                IntRect::new(4, 0, 1, 3),
+                IntRect::new(0, 3, 3, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_different_widths_apart() {
+        let rects = merge(&["###", "##.", "##."]);
+
+        assert_eq!(rects, [IntRect::new(0, 0, 3, 1), IntRect::new(0, 1, 2, 2)]);
+    }
+
+    #[test]
+    fn empty_maps_have_no_rects() {
+        assert!(merge(&[]).is_empty());
+        assert!(merge(&["...", "..."]).is_empty());
+        assert_eq!(merge(&["###", "###"]), [IntRect::new(0, 0, 3, 2)]);
+    }
+}
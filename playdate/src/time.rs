@@ -0,0 +1,581 @@
+use crate::error::{Error, Result};
+use alloc::{format, string::String};
+use core::{
+    fmt::{self, Display, Write},
+    ops::{Add, AddAssign, Sub, SubAssign},
+    str::FromStr,
+    time::Duration,
+};
+use playdate_sys::PDDateTime;
+
+// The Playdate epoch is midnight on January 1st 2000 UTC
+const EPOCH_DAYS_FROM_UNIX: i64 = 10_957;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HourFormat {
+    TwelveHour,
+    TwentyFourHour,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Weekday {
+    Monday = 1,
+    Tuesday = 2,
+    Wednesday = 3,
+    Thursday = 4,
+    Friday = 5,
+    Saturday = 6,
+    Sunday = 7,
+}
+
+impl Weekday {
+    pub fn from_number(number: u8) -> Option<Self> {
+        Some(match number {
+            1 => Self::Monday,
+            2 => Self::Tuesday,
+            3 => Self::Wednesday,
+            4 => Self::Thursday,
+            5 => Self::Friday,
+            6 => Self::Saturday,
+            7 => Self::Sunday,
+            _ => None?,
+        })
+    }
+
+    pub fn number(&self) -> u8 {
+        *self as u8
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Monday => "Monday",
+            Self::Tuesday => "Tuesday",
+            Self::Wednesday => "Wednesday",
+            Self::Thursday => "Thursday",
+            Self::Friday => "Friday",
+            Self::Saturday => "Saturday",
+            Self::Sunday => "Sunday",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Month {
+    January = 1,
+    February = 2,
+    March = 3,
+    April = 4,
+    May = 5,
+    June = 6,
+    July = 7,
+    August = 8,
+    September = 9,
+    October = 10,
+    November = 11,
+    December = 12,
+}
+
+impl Month {
+    pub fn from_number(number: u8) -> Option<Self> {
+        Some(match number {
+            1 => Self::January,
+            2 => Self::February,
+            3 => Self::March,
+            4 => Self::April,
+            5 => Self::May,
+            6 => Self::June,
+            7 => Self::July,
+            8 => Self::August,
+            9 => Self::September,
+            10 => Self::October,
+            11 => Self::November,
+            12 => Self::December,
+            _ => None?,
+        })
+    }
+
+    pub fn number(&self) -> u8 {
+        *self as u8
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::January => "January",
+            Self::February => "February",
+            Self::March => "March",
+            Self::April => "April",
+            Self::May => "May",
+            Self::June => "June",
+            Self::July => "July",
+            Self::August => "August",
+            Self::September => "September",
+            Self::October => "October",
+            Self::November => "November",
+            Self::December => "December",
+        }
+    }
+
+    pub fn days(&self, year: u16) -> u8 {
+        match self {
+            Self::February if is_leap_year(year) => 29,
+            Self::February => 28,
+            Self::April | Self::June | Self::September | Self::November => 30,
+            _ => 31,
+        }
+    }
+}
+
+pub fn is_leap_year(year: u16) -> bool {
+    match (year % 4, year % 100, year % 400) {
+        (_, _, 0) => true,
+        (_, 0, _) => false,
+        (0, _, _) => true,
+        _ => false,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DateTime {
+    year: u16,
+    month: Month,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl DateTime {
+    pub fn new(year: u16, month: Month, day: u8, hour: u8, minute: u8, second: u8) -> Option<Self> {
+        let valid = day >= 1 && day <= month.days(year) && hour < 24 && minute < 60 && second < 60;
+
+        valid.then_some(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    pub fn from_epoch(epoch: u32) -> Self {
+        Self::from_timestamp(epoch as i64)
+    }
+
+    // Timestamps are seconds relative to the Playdate epoch and may be negative, which
+    // keeps arithmetic on dates before 2000 well defined
+    pub fn from_timestamp(timestamp: i64) -> Self {
+        let days = timestamp.div_euclid(SECONDS_PER_DAY);
+        let seconds = timestamp.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days + EPOCH_DAYS_FROM_UNIX);
+
+        Self {
+            year: year as u16,
+            month: Month::from_number(month as u8).unwrap(),
+            day: day as u8,
+            hour: (seconds / 3600) as u8,
+            minute: (seconds % 3600 / 60) as u8,
+            second: (seconds % 60) as u8,
+        }
+    }
+
+    pub fn timestamp(&self) -> i64 {
+        let days = self.days_since_epoch();
+        let seconds = self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64;
+        days * SECONDS_PER_DAY + seconds
+    }
+
+    pub fn epoch(&self) -> Option<u32> {
+        self.timestamp().try_into().ok()
+    }
+
+    pub fn days_since_epoch(&self) -> i64 {
+        days_from_civil(
+            self.year as i64,
+            self.month.number() as i64,
+            self.day as i64,
+        ) - EPOCH_DAYS_FROM_UNIX
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn month(&self) -> Month {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        // The epoch fell on a Saturday
+        let number = (self.days_since_epoch() + 5).rem_euclid(7) + 1;
+        Weekday::from_number(number as u8).unwrap()
+    }
+
+    pub fn start_of_day(&self) -> Self {
+        Self {
+            hour: 0,
+            minute: 0,
+            second: 0,
+            ..*self
+        }
+    }
+
+    // `offset` is in seconds, as returned by System::timezone_offset()
+    pub fn with_timezone_offset(&self, offset: i32) -> Self {
+        Self::from_timestamp(self.timestamp() + offset as i64)
+    }
+
+    pub fn checked_duration_since(&self, earlier: &DateTime) -> Option<Duration> {
+        let seconds = self.timestamp() - earlier.timestamp();
+        (seconds >= 0).then(|| Duration::from_secs(seconds as u64))
+    }
+
+    pub fn hour_12(&self) -> (u8, bool) {
+        let is_pm = self.hour >= 12;
+        let hour = match self.hour % 12 {
+            0 => 12,
+            hour => hour,
+        };
+
+        (hour, is_pm)
+    }
+
+    pub fn format_time(&self, hour_format: HourFormat) -> String {
+        match hour_format {
+            HourFormat::TwentyFourHour => self.format("%H:%M"),
+            HourFormat::TwelveHour => self.format("%-I:%M %p"),
+        }
+    }
+
+    // Supports a strftime-like subset: %Y %m %d %e %H %I %M %S %p %A %a %B %b %j and %%.
+    // A `-` after the `%` drops zero padding, e.g. %-I
+    pub fn format(&self, pattern: &str) -> String {
+        let mut out = String::new();
+        let mut chars = pattern.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+
+            let mut spec = chars.next();
+            let pad = spec != Some('-');
+            if !pad {
+                spec = chars.next();
+            }
+
+            let num = |out: &mut String, n: u32| {
+                if pad {
+                    write!(out, "{:02}", n)
+                } else {
+                    write!(out, "{}", n)
+                }
+            };
+
+            let (hour_12, is_pm) = self.hour_12();
+            let _ = match spec {
+                Some('Y') => write!(out, "{}", self.year),
+                Some('m') => num(&mut out, self.month.number() as u32),
+                Some('d') => num(&mut out, self.day as u32),
+                Some('e') => write!(out, "{:>2}", self.day),
+                Some('H') => num(&mut out, self.hour as u32),
+                Some('I') => num(&mut out, hour_12 as u32),
+                Some('M') => num(&mut out, self.minute as u32),
+                Some('S') => num(&mut out, self.second as u32),
+                Some('p') => out.write_str(if is_pm { "PM" } else { "AM" }),
+                Some('A') => out.write_str(self.weekday().name()),
+                Some('a') => out.write_str(&self.weekday().name()[..3]),
+                Some('B') => out.write_str(self.month.name()),
+                Some('b') => out.write_str(&self.month.name()[..3]),
+                Some('j') => write!(out, "{:03}", self.day_of_year()),
+                Some('%') => out.write_char('%'),
+                Some(other) => write!(out, "%{}", other),
+                None => out.write_char('%'),
+            };
+        }
+
+        out
+    }
+
+    pub fn day_of_year(&self) -> u16 {
+        let start = days_from_civil(self.year as i64, 1, 1);
+        let today = days_from_civil(
+            self.year as i64,
+            self.month.number() as i64,
+            self.day as i64,
+        );
+        (today - start + 1) as u16
+    }
+
+    // Accepts `YYYY-MM-DD`, optionally followed by a space or `T` and `HH:MM` or `HH:MM:SS`,
+    // with an optional trailing `Z`
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid = || Error::new(format!("invalid date: {}", text));
+        let text = text.trim();
+        let text = text.strip_suffix('Z').unwrap_or(text);
+
+        let (date, time) = match text.find(['T', ' ']) {
+            Some(i) => (&text[..i], Some(&text[i + 1..])),
+            None => (text, None),
+        };
+
+        let mut date_parts = date.split('-');
+        let year = parse_field(date_parts.next()).ok_or_else(invalid)?;
+        let month = parse_field(date_parts.next())
+            .and_then(Month::from_number)
+            .ok_or_else(invalid)?;
+        let day = parse_field(date_parts.next()).ok_or_else(invalid)?;
+
+        if date_parts.next().is_some() {
+            return Err(invalid());
+        }
+
+        let (hour, minute, second) = match time {
+            Some(time) => {
+                let mut time_parts = time.split(':');
+                let hour = parse_field(time_parts.next()).ok_or_else(invalid)?;
+                let minute = parse_field(time_parts.next()).ok_or_else(invalid)?;
+                let second = match time_parts.next() {
+                    Some(second) => parse_field(Some(second)).ok_or_else(invalid)?,
+                    None => 0,
+                };
+
+                if time_parts.next().is_some() {
+                    return Err(invalid());
+                }
+
+                (hour, minute, second)
+            }
+            None => (0, 0, 0),
+        };
+
+        Self::new(year, month, day, hour, minute, second).ok_or_else(invalid)
+    }
+}
+
+fn parse_field<T: FromStr>(field: Option<&str>) -> Option<T> {
+    field
+        .filter(|f| !f.is_empty() && f.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|f| f.parse().ok())
+}
+
+impl Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year,
+            self.month.number(),
+            self.day,
+            self.hour,
+            self.minute,
+            self.second
+        )
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl Add<Duration> for DateTime {
+    type Output = DateTime;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        Self::from_timestamp(self.timestamp() + rhs.as_secs() as i64)
+    }
+}
+
+impl AddAssign<Duration> for DateTime {
+    fn add_assign(&mut self, rhs: Duration) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Duration> for DateTime {
+    type Output = DateTime;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        Self::from_timestamp(self.timestamp() - rhs.as_secs() as i64)
+    }
+}
+
+impl SubAssign<Duration> for DateTime {
+    fn sub_assign(&mut self, rhs: Duration) {
+        *self = *self - rhs;
+    }
+}
+
+// Like Instant, subtracting a later date saturates to zero rather than panicking
+impl Sub<DateTime> for DateTime {
+    type Output = Duration;
+
+    fn sub(self, rhs: DateTime) -> Self::Output {
+        self.checked_duration_since(&rhs).unwrap_or_default()
+    }
+}
+
+impl From<PDDateTime> for DateTime {
+    fn from(value: PDDateTime) -> Self {
+        Self {
+            year: value.year,
+            month: Month::from_number(value.month).unwrap_or(Month::January),
+            day: value.day,
+            hour: value.hour,
+            minute: value.minute,
+            second: value.second,
+        }
+    }
+}
+
+impl From<DateTime> for PDDateTime {
+    fn from(value: DateTime) -> Self {
+        Self {
+            year: value.year,
+            month: value.month.number(),
+            day: value.day,
+            weekday: value.weekday().number(),
+            hour: value.hour,
+            minute: value.minute,
+            second: value.second,
+        }
+    }
+}
+
+// Civil calendar conversions from Howard Hinnant's date algorithms, in days since
+// 1970-01-01 on the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn date(text: &str) -> DateTime {
+        DateTime::parse(text).unwrap()
+    }
+
+    #[test]
+    fn converts_timestamps() {
+        let epoch = DateTime::from_epoch(0);
+        assert_eq!(epoch, date("2000-01-01"));
+        assert_eq!(epoch.weekday(), Weekday::Saturday);
+
+        // 1700000000 in Unix time
+        let later = DateTime::from_epoch(753_315_200);
+        assert_eq!(later, date("2023-11-14T22:13:20Z"));
+        assert_eq!(later.epoch(), Some(753_315_200));
+
+        let before = DateTime::from_timestamp(-1);
+        assert_eq!(before, date("1999-12-31 23:59:59"));
+        assert_eq!(before.epoch(), None);
+        assert_eq!(before.timestamp(), -1);
+    }
+
+    #[test]
+    fn handles_leap_years() {
+        assert!(is_leap_year(2000));
+        assert!(is_leap_year(2024));
+        assert!(!is_leap_year(1900));
+        assert!(!is_leap_year(2023));
+
+        assert_eq!(Month::February.days(2024), 29);
+        assert_eq!(Month::February.days(2023), 28);
+        assert!(DateTime::new(2023, Month::February, 29, 0, 0, 0).is_none());
+
+        let leap_day = date("2024-02-29");
+        assert_eq!(leap_day.weekday(), Weekday::Thursday);
+        assert_eq!(leap_day.day_of_year(), 60);
+        assert_eq!(leap_day + Duration::from_secs(86_400), date("2024-03-01"));
+    }
+
+    #[test]
+    fn does_arithmetic() {
+        let start = date("2023-12-31 23:00");
+        let end = start + Duration::from_secs(7200);
+        assert_eq!(end, date("2024-01-01 01:00"));
+        assert_eq!(end - start, Duration::from_secs(7200));
+        assert_eq!(start - end, Duration::ZERO);
+        assert_eq!(end - Duration::from_secs(7200), start);
+        assert_eq!(end.start_of_day(), date("2024-01-01"));
+        assert_eq!(start.with_timezone_offset(3600), date("2024-01-01"));
+    }
+
+    #[test]
+    fn formats() {
+        let date = date("2024-03-05T14:07:09");
+        assert_eq!(date.to_string(), "2024-03-05 14:07:09");
+        assert_eq!(date.format("%A %e %B %Y, %j"), "Tuesday  5 March 2024, 065");
+        assert_eq!(date.format("%a %-d %b %H:%M:%S %%"), "Tue 5 Mar 14:07:09 %");
+        assert_eq!(date.format_time(HourFormat::TwelveHour), "2:07 PM");
+        assert_eq!(date.format_time(HourFormat::TwentyFourHour), "14:07");
+        assert_eq!(date.start_of_day().format("%I %p"), "12 AM");
+        assert_eq!(date.format("%q"), "%q");
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        for text in [
+            "",
+            "2024",
+            "2024-13-01",
+            "2024-04-31",
+            "2024-01-01-01",
+            "2024-01-01 24:00",
+            "2024-01-01 12",
+            "2024-01-01 12:00:00:00",
+            "2024-+1-01",
+        ] {
+            assert!(DateTime::parse(text).is_err(), "{}", text);
+        }
+    }
+}
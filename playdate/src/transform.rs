@@ -0,0 +1,259 @@
+use crate::{
+    geometry::{LineSegment, Point, Rect},
+    math::{cos, sin, tan},
+};
+use alloc::vec::Vec;
+use core::{f32::consts::PI, ops::Mul};
+
+// Maps (x, y) to (m11 * x + m12 * y + tx, m21 * x + m22 * y + ty), matching the fields of
+// `playdate.geometry.affineTransform` in the Lua SDK. Angles are in degrees and positive
+// rotations turn clockwise on screen, the same as `Bitmap::draw_rotated`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AffineTransform {
+    pub m11: f32,
+    pub m12: f32,
+    pub m21: f32,
+    pub m22: f32,
+    pub tx: f32,
+    pub ty: f32,
+}
+
+impl AffineTransform {
+    pub const IDENTITY: Self = Self::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+
+    pub const fn new(m11: f32, m12: f32, m21: f32, m22: f32, tx: f32, ty: f32) -> Self {
+        Self {
+            m11,
+            m12,
+            m21,
+            m22,
+            tx,
+            ty,
+        }
+    }
+
+    pub const fn translation(dx: f32, dy: f32) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, dx, dy)
+    }
+
+    pub fn rotation(degrees: f32) -> Self {
+        let radians = degrees * PI / 180.0;
+        let (sin, cos) = (sin(radians), cos(radians));
+        Self::new(cos, -sin, sin, cos, 0.0, 0.0)
+    }
+
+    pub fn rotation_about(degrees: f32, center: Point) -> Self {
+        Self::translation(-center.x, -center.y)
+            .rotate(degrees)
+            .translate(center.x, center.y)
+    }
+
+    pub const fn scaling(sx: f32, sy: f32) -> Self {
+        Self::new(sx, 0.0, 0.0, sy, 0.0, 0.0)
+    }
+
+    pub fn skewing(sx_degrees: f32, sy_degrees: f32) -> Self {
+        let sx = tan(sx_degrees * PI / 180.0);
+        let sy = tan(sy_degrees * PI / 180.0);
+        Self::new(1.0, sx, sy, 1.0, 0.0, 0.0)
+    }
+
+    // The builders below apply their operation after the existing transform, so
+    // `AffineTransform::scaling(2.0, 2.0).translate(10.0, 0.0)` scales and then translates
+    pub fn translate(self, dx: f32, dy: f32) -> Self {
+        self.then(&Self::translation(dx, dy))
+    }
+
+    pub fn rotate(self, degrees: f32) -> Self {
+        self.then(&Self::rotation(degrees))
+    }
+
+    pub fn scale(self, sx: f32, sy: f32) -> Self {
+        self.then(&Self::scaling(sx, sy))
+    }
+
+    pub fn skew(self, sx_degrees: f32, sy_degrees: f32) -> Self {
+        self.then(&Self::skewing(sx_degrees, sy_degrees))
+    }
+
+    // The transform that applies `self` first and `other` second
+    pub fn then(&self, other: &Self) -> Self {
+        Self::new(
+            other.m11 * self.m11 + other.m12 * self.m21,
+            other.m11 * self.m12 + other.m12 * self.m22,
+            other.m21 * self.m11 + other.m22 * self.m21,
+            other.m21 * self.m12 + other.m22 * self.m22,
+            other.m11 * self.tx + other.m12 * self.ty + other.tx,
+            other.m21 * self.tx + other.m22 * self.ty + other.ty,
+        )
+    }
+
+    // Transforms that collapse everything onto a line or a point can't be undone
+    pub fn invert(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let m11 = self.m22 / det;
+        let m12 = -self.m12 / det;
+        let m21 = -self.m21 / det;
+        let m22 = self.m11 / det;
+
+        Some(Self::new(
+            m11,
+            m12,
+            m21,
+            m22,
+            -(m11 * self.tx + m12 * self.ty),
+            -(m21 * self.tx + m22 * self.ty),
+        ))
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.m11 * self.m22 - self.m12 * self.m21
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    pub fn transform_point(&self, point: Point) -> Point {
+        Point::new(
+            self.m11 * point.x + self.m12 * point.y + self.tx,
+            self.m21 * point.x + self.m22 * point.y + self.ty,
+        )
+    }
+
+    pub fn transform_points(&self, points: &[Point]) -> Vec<Point> {
+        points
+            .iter()
+            .map(|&point| self.transform_point(point))
+            .collect()
+    }
+
+    pub fn transform_line(&self, line: LineSegment) -> LineSegment {
+        LineSegment::new(
+            self.transform_point(line.start),
+            self.transform_point(line.end),
+        )
+    }
+
+    // Rotating or skewing a rect doesn't produce a rect, so this returns its four corners
+    // in clockwise order starting from the top left
+    pub fn transform_rect(&self, rect: Rect) -> [Point; 4] {
+        [
+            Point::new(rect.min_x(), rect.min_y()),
+            Point::new(rect.max_x(), rect.min_y()),
+            Point::new(rect.max_x(), rect.max_y()),
+            Point::new(rect.min_x(), rect.max_y()),
+        ]
+        .map(|point| self.transform_point(point))
+    }
+
+    // The axis-aligned bounds of the transformed rect
+    pub fn transform_bounds(&self, rect: Rect) -> Rect {
+        let corners = self.transform_rect(rect);
+        let (mut min, mut max) = (corners[0], corners[0]);
+
+        for corner in &corners[1..] {
+            min = Point::new(min.x.min(corner.x), min.y.min(corner.y));
+            max = Point::new(max.x.max(corner.x), max.y.max(corner.y));
+        }
+
+        Rect::from_points(min, max)
+    }
+}
+
+impl Default for AffineTransform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+// `a * b` is `a.then(&b)`, like concatenating transforms in the Lua SDK
+impl Mul for AffineTransform {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.then(&rhs)
+    }
+}
+
+impl Mul<Point> for AffineTransform {
+    type Output = Point;
+
+    fn mul(self, rhs: Point) -> Self::Output {
+        self.transform_point(rhs)
+    }
+}
+
+impl Mul<LineSegment> for AffineTransform {
+    type Output = LineSegment;
+
+    fn mul(self, rhs: LineSegment) -> Self::Output {
+        self.transform_line(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Point, b: Point) -> bool {
+        (a.x - b.x).abs() < 1e-4 && (a.y - b.y).abs() < 1e-4
+    }
+
+    #[test]
+    fn applies_in_order() {
+        let transform = AffineTransform::scaling(2.0, 2.0).translate(10.0, 0.0);
+        assert_eq!(transform * Point::new(1.0, 1.0), Point::new(12.0, 2.0));
+
+        let transform = AffineTransform::translation(10.0, 0.0).scale(2.0, 2.0);
+        assert_eq!(transform * Point::new(1.0, 1.0), Point::new(22.0, 2.0));
+    }
+
+    #[test]
+    fn rotates_clockwise() {
+        let point = AffineTransform::rotation(90.0) * Point::new(1.0, 0.0);
+        assert!(close(point, Point::new(0.0, 1.0)));
+
+        let transform = AffineTransform::rotation_about(180.0, Point::new(5.0, 5.0));
+        assert!(close(
+            transform * Point::new(0.0, 0.0),
+            Point::new(10.0, 10.0)
+        ));
+    }
+
+    #[test]
+    fn inverts() {
+        let transform = AffineTransform::rotation(30.0)
+            .scale(2.0, 3.0)
+            .skew(10.0, 0.0)
+            .translate(4.0, -7.0);
+        let inverse = transform.invert().unwrap();
+        let point = Point::new(3.0, 8.0);
+
+        assert!(close(inverse * (transform * point), point));
+        assert!(close(
+            (transform * inverse) * point,
+            AffineTransform::IDENTITY * point
+        ));
+        assert_eq!(AffineTransform::scaling(0.0, 1.0).invert(), None);
+        assert!(AffineTransform::default().is_identity());
+    }
+
+    #[test]
+    fn transforms_rects() {
+        let rect = Rect::new(0.0, 0.0, 4.0, 2.0);
+        let transform = AffineTransform::rotation(90.0);
+        let bounds = transform.transform_bounds(rect);
+
+        assert!(close(bounds.origin(), Point::new(-2.0, 0.0)));
+        assert!((bounds.width - 2.0).abs() < 1e-4 && (bounds.height - 4.0).abs() < 1e-4);
+        assert!(close(
+            transform.transform_rect(rect)[1],
+            Point::new(0.0, 4.0)
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{
+    easing::Easing,
+    geometry::{IntPoint, IntRect, Point, Rect},
+    math::round,
+    system::System,
+};
+
+pub trait Lerp: Copy {
+    fn lerp(from: Self, to: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(from: Self, to: Self, t: f32) -> Self {
+        from + (to - from) * t
+    }
+}
+
+impl Lerp for i32 {
+    fn lerp(from: Self, to: Self, t: f32) -> Self {
+        round(f32::lerp(from as f32, to as f32, t)) as i32
+    }
+}
+
+impl Lerp for Point {
+    fn lerp(from: Self, to: Self, t: f32) -> Self {
+        Self {
+            x: f32::lerp(from.x, to.x, t),
+            y: f32::lerp(from.y, to.y, t),
+        }
+    }
+}
+
+impl Lerp for IntPoint {
+    fn lerp(from: Self, to: Self, t: f32) -> Self {
+        Self {
+            x: i32::lerp(from.x, to.x, t),
+            y: i32::lerp(from.y, to.y, t),
+        }
+    }
+}
+
+impl Lerp for Rect {
+    fn lerp(from: Self, to: Self, t: f32) -> Self {
+        Self {
+            x: f32::lerp(from.x, to.x, t),
+            y: f32::lerp(from.y, to.y, t),
+            width: f32::lerp(from.width, to.width, t),
+            height: f32::lerp(from.height, to.height, t),
+        }
+    }
+}
+
+impl Lerp for IntRect {
+    fn lerp(from: Self, to: Self, t: f32) -> Self {
+        Self {
+            x: i32::lerp(from.x, to.x, t),
+            y: i32::lerp(from.y, to.y, t),
+            width: i32::lerp(from.width, to.width, t),
+            height: i32::lerp(from.height, to.height, t),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TweenMode {
+    Once,
+    Reverse,
+    Loop,
+    Yoyo,
+}
+
+#[derive(Clone, Debug)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    duration: u32,
+    elapsed: u32,
+    easing: Easing,
+    mode: TweenMode,
+    last_tick: Option<u32>,
+}
+
+impl<T> Tween<T>
+where
+    T: Lerp,
+{
+    pub fn new(from: T, to: T, duration: u32) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            elapsed: 0,
+            easing: Easing::Linear,
+            mode: TweenMode::Once,
+            last_tick: None,
+        }
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn mode(mut self, mode: TweenMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn advance(&mut self, delta: u32) -> T {
+        self.elapsed = self.elapsed.saturating_add(delta);
+        self.value()
+    }
+
+    pub fn update(&mut self, system: &System) -> T {
+        // The first update only records the clock so the tween starts from `from`
+        let now = system.current_time_milliseconds();
+        let delta = self
+            .last_tick
+            .map(|last| now.wrapping_sub(last))
+            .unwrap_or(0);
+        self.last_tick = Some(now);
+        self.advance(delta)
+    }
+
+    pub fn value(&self) -> T {
+        T::lerp(self.from, self.to, self.easing.apply(self.progress()))
+    }
+
+    pub fn progress(&self) -> f32 {
+        let (cycle, phase) = if self.duration == 0 || self.is_finished() {
+            (0, 1.0)
+        } else {
+            let cycle = self.elapsed / self.duration;
+            let phase = (self.elapsed % self.duration) as f32 / self.duration as f32;
+            (cycle, phase)
+        };
+
+        match self.mode {
+            TweenMode::Once | TweenMode::Loop => phase,
+            TweenMode::Reverse => 1.0 - phase,
+            TweenMode::Yoyo if cycle % 2 == 0 => phase,
+            TweenMode::Yoyo => 1.0 - phase,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !self.repeats() && self.elapsed >= self.duration
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+        self.last_tick = None;
+    }
+
+    pub fn elapsed(&self) -> u32 {
+        self.elapsed
+    }
+
+    pub fn duration(&self) -> u32 {
+        self.duration
+    }
+
+    fn repeats(&self) -> bool {
+        matches!(self.mode, TweenMode::Loop | TweenMode::Yoyo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_once() {
+        let mut tween = Tween::new(0.0, 10.0, 100);
+        assert_eq!(tween.value(), 0.0);
+        assert_eq!(tween.advance(25), 2.5);
+        assert!(!tween.is_finished());
+        assert_eq!(tween.advance(100), 10.0);
+        assert!(tween.is_finished());
+
+        tween.reset();
+        assert_eq!(tween.value(), 0.0);
+    }
+
+    #[test]
+    fn repeats() {
+        let mut tween = Tween::new(0, 100, 100).mode(TweenMode::Loop);
+        assert_eq!(tween.advance(150), 50);
+        assert!(!tween.is_finished());
+
+        let mut tween = Tween::new(0, 100, 100).mode(TweenMode::Yoyo);
+        assert_eq!(tween.advance(125), 75);
+        assert_eq!(tween.advance(100), 25);
+
+        let mut tween = Tween::new(0, 100, 100).mode(TweenMode::Reverse);
+        assert_eq!(tween.advance(25), 75);
+        assert_eq!(tween.advance(100), 0);
+    }
+
+    #[test]
+    fn zero_duration_finishes_immediately() {
+        let tween = Tween::new(1.0, 2.0, 0);
+        assert!(tween.is_finished());
+        assert_eq!(tween.value(), 2.0);
+    }
+
+    #[test]
+    fn lerps_geometry() {
+        let from = IntRect::new(0, 0, 10, 10);
+        let to = IntRect::new(10, -10, 20, 0);
+        assert_eq!(IntRect::lerp(from, to, 0.5), IntRect::new(5, -5, 15, 5));
+
+        let point = Point::lerp(Point::new(0.0, 0.0), Point::new(2.0, 4.0), 0.25);
+        assert_eq!(point, Point::new(0.5, 1.0));
+    }
+}
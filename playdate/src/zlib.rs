@@ -0,0 +1,479 @@
+use crate::error::{Error, Result};
+use alloc::{format, vec, vec::Vec};
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+fn error(message: &str) -> Error {
+    Error::new(format!("invalid deflate data: {}", message))
+}
+
+// Reads bits least significant first, as deflate packs them
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buffer: u32,
+    count: u32,
+}
+
+impl<'a> Bits<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn bits(&mut self, n: u32) -> Result<u32> {
+        while self.count < n {
+            let byte = *self
+                .data
+                .get(self.pos)
+                .ok_or_else(|| error("unexpected end of data"))?;
+            self.buffer |= (byte as u32) << self.count;
+            self.pos += 1;
+            self.count += 8;
+        }
+
+        let value = self.buffer & ((1 << n) - 1);
+        self.buffer >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    // Skips to the next byte boundary and reads the rest as whole bytes
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        self.buffer = 0;
+        self.count = 0;
+
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| error("unexpected end of data"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+}
+
+// Canonical Huffman codes stored as the number of codes of each length and the symbols
+// in code order
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self> {
+        let mut counts = [0; 16];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(error("over-subscribed code lengths"));
+            }
+        }
+
+        let mut offsets = [0; 16];
+        for len in 1..15 {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, bits: &mut Bits) -> Result<u16> {
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+
+        for &count in &self.counts[1..] {
+            code |= bits.bits(1)? as i32;
+            let count = count as i32;
+
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err(error("invalid code"))
+    }
+}
+
+// Fails rather than producing more than `limit` bytes, since the data may claim any size
+pub(crate) fn decompress(data: &[u8], limit: usize) -> Result<Vec<u8>> {
+    let [cmf, flg, ..] = *data else {
+        return Err(error("missing zlib header"));
+    };
+
+    if cmf & 0x0f != 8 || u16::from_be_bytes([cmf, flg]) % 31 > 0 {
+        return Err(error("bad zlib header"));
+    }
+
+    if flg & 0x20 != 0 {
+        return Err(error("preset dictionaries are not supported"));
+    }
+
+    inflate(&data[2..], limit)
+}
+
+pub(crate) fn inflate(data: &[u8], limit: usize) -> Result<Vec<u8>> {
+    let mut bits = Bits::new(data);
+    let mut out = Vec::new();
+
+    loop {
+        let last = bits.bits(1)? == 1;
+
+        match bits.bits(2)? {
+            0 => {
+                let header = bits.bytes(4)?;
+                let len = u16::from_le_bytes([header[0], header[1]]);
+                let inverse = u16::from_le_bytes([header[2], header[3]]);
+                if len != !inverse {
+                    return Err(error("stored block length mismatch"));
+                }
+
+                if len as usize > limit - out.len() {
+                    return Err(error("data is larger than expected"));
+                }
+
+                out.extend_from_slice(bits.bytes(len as usize)?);
+            }
+            1 => {
+                let (literals, distances) = fixed_codes()?;
+                inflate_block(&mut bits, &mut out, limit, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(&mut bits)?;
+                inflate_block(&mut bits, &mut out, limit, &literals, &distances)?;
+            }
+            _ => return Err(error("invalid block type")),
+        }
+
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+fn fixed_codes() -> Result<(Huffman, Huffman)> {
+    let mut lengths = [0; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+
+    Ok((Huffman::new(&lengths)?, Huffman::new(&[5; 30])?))
+}
+
+fn dynamic_codes(bits: &mut Bits) -> Result<(Huffman, Huffman)> {
+    let literal_count = bits.bits(5)? as usize + 257;
+    let distance_count = bits.bits(5)? as usize + 1;
+    let code_length_count = bits.bits(4)? as usize + 4;
+
+    let mut code_lengths = [0; 19];
+    for &i in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[i] = bits.bits(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&code_lengths)?;
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (len, repeat) = match code_lengths.decode(bits)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *lengths
+                    .last()
+                    .ok_or_else(|| error("repeat with no length"))?;
+                (previous, bits.bits(2)? + 3)
+            }
+            17 => (0, bits.bits(3)? + 3),
+            _ => (0, bits.bits(7)? + 11),
+        };
+
+        for _ in 0..repeat {
+            lengths.push(len);
+        }
+    }
+
+    if lengths.len() > literal_count + distance_count {
+        return Err(error("too many code lengths"));
+    }
+
+    if lengths[256] == 0 {
+        return Err(error("missing end of block code"));
+    }
+
+    let literals = Huffman::new(&lengths[..literal_count])?;
+    let distances = Huffman::new(&lengths[literal_count..])?;
+    Ok((literals, distances))
+}
+
+fn inflate_block(
+    bits: &mut Bits,
+    out: &mut Vec<u8>,
+    limit: usize,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<()> {
+    let too_large = || error("data is larger than expected");
+
+    loop {
+        let symbol = literals.decode(bits)? as usize;
+
+        if symbol < 256 {
+            if out.len() == limit {
+                return Err(too_large());
+            }
+
+            out.push(symbol as u8);
+            continue;
+        }
+
+        if symbol == 256 {
+            return Ok(());
+        }
+
+        let index = symbol - 257;
+        if index >= LENGTH_BASE.len() {
+            return Err(error("invalid length code"));
+        }
+        let len = LENGTH_BASE[index] as usize + bits.bits(LENGTH_EXTRA[index] as u32)? as usize;
+
+        let index = distances.decode(bits)? as usize;
+        if index >= DISTANCE_BASE.len() {
+            return Err(error("invalid distance code"));
+        }
+        let distance =
+            DISTANCE_BASE[index] as usize + bits.bits(DISTANCE_EXTRA[index] as u32)? as usize;
+
+        if distance > out.len() {
+            return Err(error("distance too far back"));
+        }
+
+        if len > limit - out.len() {
+            return Err(too_large());
+        }
+
+        // The copy may overlap what it's producing, so it has to go byte by byte
+        let start = out.len() - distance;
+        for i in 0..len {
+            out.push(out[start + i]);
+        }
+    }
+}
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const HASH_BITS: u32 = 12;
+
+// Writes bits least significant first
+struct BitWriter {
+    out: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn bits(&mut self, value: u32, n: u32) {
+        self.buffer |= value << self.count;
+        self.count += n;
+
+        while self.count >= 8 {
+            self.out.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    // Huffman codes are packed starting from their most significant bit
+    fn code(&mut self, code: u32, len: u32) {
+        self.bits(code.reverse_bits() >> (32 - len), len);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.buffer as u8);
+        }
+
+        self.out
+    }
+}
+
+pub(crate) fn compress(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter {
+        out: Vec::from([0x78, 0x01]),
+        buffer: 0,
+        count: 0,
+    };
+
+    // A single final block using the fixed codes, which does well enough on the long runs
+    // in 1 bit images without having to build dynamic tables
+    writer.bits(1, 1);
+    writer.bits(1, 2);
+    deflate_fixed(&mut writer, data);
+    write_literal(&mut writer, 256);
+
+    let mut out = writer.finish();
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+// Greedy LZ77, remembering only the most recent position of each 3 byte sequence
+fn deflate_fixed(writer: &mut BitWriter, data: &[u8]) {
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut pos = 0;
+
+    while pos < data.len() {
+        let mut len = 0;
+        let mut distance = 0;
+
+        if pos + MIN_MATCH <= data.len() {
+            let hash = hash(&data[pos..pos + MIN_MATCH]);
+            let candidate = head[hash];
+            head[hash] = pos;
+
+            if candidate != usize::MAX && pos - candidate <= WINDOW_SIZE {
+                let max = (data.len() - pos).min(MAX_MATCH);
+                len = (0..max)
+                    .take_while(|&i| data[candidate + i] == data[pos + i])
+                    .count();
+                distance = pos - candidate;
+            }
+        }
+
+        if len < MIN_MATCH {
+            write_literal(writer, data[pos] as usize);
+            pos += 1;
+            continue;
+        }
+
+        write_match(writer, len, distance);
+
+        // Remember the skipped positions too, so later runs can refer back into this one
+        for i in pos + 1..pos + len {
+            if i + MIN_MATCH <= data.len() {
+                head[hash(&data[i..i + MIN_MATCH])] = i;
+            }
+        }
+
+        pos += len;
+    }
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let value = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+    (value.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+}
+
+fn write_literal(writer: &mut BitWriter, symbol: usize) {
+    let symbol = symbol as u32;
+    match symbol {
+        0..=143 => writer.code(0x30 + symbol, 8),
+        144..=255 => writer.code(0x190 + symbol - 144, 9),
+        256..=279 => writer.code(symbol - 256, 7),
+        _ => writer.code(0xc0 + symbol - 280, 8),
+    }
+}
+
+fn write_match(writer: &mut BitWriter, len: usize, distance: usize) {
+    let index = LENGTH_BASE
+        .iter()
+        .rposition(|&base| base as usize <= len)
+        .unwrap();
+    write_literal(writer, 257 + index);
+    writer.bits(
+        (len - LENGTH_BASE[index] as usize) as u32,
+        LENGTH_EXTRA[index] as u32,
+    );
+
+    let index = DISTANCE_BASE
+        .iter()
+        .rposition(|&base| base as usize <= distance)
+        .unwrap();
+    writer.code(index as u32, 5);
+    writer.bits(
+        (distance - DISTANCE_BASE[index] as usize) as u32,
+        DISTANCE_EXTRA[index] as u32,
+    );
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decompresses_zlib_output() {
+        // zlib.compress(b"hello")
+        let data = [120, 156, 203, 72, 205, 201, 201, 7, 0, 6, 44, 2, 21];
+        assert_eq!(decompress(&data, 5).unwrap(), b"hello");
+        assert!(decompress(&data, 4).is_err());
+        assert!(decompress(&data[..6], 5).is_err());
+        assert!(decompress(&[0, 0], 5).is_err());
+    }
+
+    #[test]
+    fn round_trips() {
+        let text = b"the quick brown fox jumps over the lazy dog ".repeat(50);
+        let noise: Vec<u8> = (0..5000u32).map(|i| (i * 7919 % 251) as u8).collect();
+
+        for data in [&[][..], b"a", &text, &noise, &[0; 70000]] {
+            let compressed = compress(data);
+            assert_eq!(decompress(&compressed, data.len()).unwrap(), data);
+        }
+
+        assert!(compress(&text).len() < text.len() / 4);
+    }
+
+    #[test]
+    fn limits_output() {
+        let compressed = compress(&[0; 1000]);
+        assert!(decompress(&compressed, 999).is_err());
+        assert_eq!(decompress(&compressed, 1000).unwrap().len(), 1000);
+    }
+}
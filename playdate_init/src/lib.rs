@@ -1,10 +1,11 @@
 mod attr;
+mod menu;
 
 use attr::AppArgs;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
-use syn::{parse_macro_input, Ident, ItemStruct};
+use syn::{parse_macro_input, DeriveInput, Ident, ItemStruct};
 
 struct MacroArgs {
     struct_ident: Ident,
@@ -46,6 +47,15 @@ pub fn pd_app(attr: TokenStream, item: TokenStream) -> TokenStream {
     TokenStream::from(output)
 }
 
+#[proc_macro_derive(MenuOptions, attributes(option))]
+pub fn derive_menu_options(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    menu::derive_menu_options(input)
+        .unwrap_or_else(|err| err.into_compile_error())
+        .into()
+}
+
 fn init(args: &MacroArgs) -> proc_macro2::TokenStream {
     let MacroArgs {
         init_ident,
@@ -76,6 +86,12 @@ fn init(args: &MacroArgs) -> proc_macro2::TokenStream {
         ) -> i32 {
             use alloc::boxed::Box;
 
+            if event == ::playdate_sys::PDSystemEvent_kEventPause {
+                let mut pd = unsafe { ::playdate::Playdate::<()>::init() };
+                pd.render_menu_image();
+                return 0
+            }
+
             if event != ::playdate_sys::PDSystemEvent_kEventInit {
                 return 0
             }
@@ -114,7 +130,9 @@ fn update(args: &MacroArgs) -> proc_macro2::TokenStream {
 
             let mut app_data = unsafe { ::alloc::boxed::Box::from_raw(ptr) };
             let mut pd = unsafe { ::playdate::Playdate::init() };
+            pd.poll_tasks();
             let frame_result = app_data.app.#update_ident(&mut pd);
+            pd.draw_debug();
             ::core::mem::forget(app_data);
             frame_result as i32
         }
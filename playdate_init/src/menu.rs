@@ -0,0 +1,84 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, LitStr, Result};
+
+pub(crate) fn derive_menu_options(input: DeriveInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "MenuOptions can only be derived for enums",
+        ));
+    };
+
+    if data.variants.is_empty() {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "MenuOptions needs at least one variant",
+        ));
+    }
+
+    let mut variants = Vec::new();
+    let mut titles = Vec::new();
+
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "MenuOptions variants cannot have fields",
+            ));
+        }
+
+        // Titles default to the variant name and can be overridden with #[option("...")]
+        let mut title = variant.ident.to_string();
+        for attr in &variant.attrs {
+            if attr.path().is_ident("option") {
+                title = attr.parse_args::<LitStr>()?.value();
+            }
+        }
+
+        if title.contains('\0') {
+            return Err(Error::new_spanned(
+                variant,
+                "option titles cannot contain NUL",
+            ));
+        }
+
+        let mut bytes = title.into_bytes();
+        bytes.push(0);
+
+        variants.push(&variant.ident);
+        titles.push(Literal::byte_string(&bytes));
+    }
+
+    let indices = 0..variants.len();
+    let to_indices = 0..variants.len();
+
+    Ok(quote! {
+        impl ::playdate::MenuValue for #ident {
+            fn from_index(index: usize) -> Option<Self> {
+                match index {
+                    #( #indices => Some(Self::#variants), )*
+                    _ => None,
+                }
+            }
+
+            fn to_index(&self) -> usize {
+                match self {
+                    #( Self::#variants => #to_indices, )*
+                }
+            }
+        }
+
+        impl ::playdate::MenuOptions for #ident {
+            fn titles() -> &'static [&'static ::core::ffi::CStr] {
+                const TITLES: &[&::core::ffi::CStr] = &[
+                    #( unsafe { ::core::ffi::CStr::from_bytes_with_nul_unchecked(#titles) }, )*
+                ];
+
+                TITLES
+            }
+        }
+    })
+}